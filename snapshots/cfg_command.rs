@@ -0,0 +1,61 @@
+pub mod cli {
+    pub struct Parameters<'a> {
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    pub fn status() {}
+    #[cfg(feature = "cloud")]
+    pub fn upload(bucket: String) {}
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command()]
+            pub struct Cli {
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                #[command()]
+                Status {},
+                #[cfg(feature = "cloud")]
+                #[command()]
+                Upload { #[arg(long)] bucket: String },
+            }
+        }
+        pub fn handle_matches(cli: &cli::Cli) -> Option<u8> {
+            let parameters = super::Parameters {
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                Some(cli::Commands::Status {}) => {
+                    super::status();
+                    None
+                }
+                #[cfg(feature = "cloud")]
+                Some(cli::Commands::Upload { bucket: p1 }) => {
+                    super::upload((p1).clone());
+                    None
+                }
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+                #[allow(unreachable_patterns)]
+                _ => unreachable!("command is not available in this build"),
+            }
+        }
+    }
+    /// Parse the command line arguments and run the selected command.
+    /// Returns the exit code of the command, if it has one.
+    pub fn exec_cli() -> Option<u8> {
+        let cli = {
+            use ::tusks::clap::Parser as _;
+            __internal_tusks_module::cli::Cli::parse()
+        };
+        __internal_tusks_module::handle_matches(&cli)
+    }
+}
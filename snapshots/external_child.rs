@@ -0,0 +1,46 @@
+pub mod cloud {
+    pub struct Parameters<'a> {
+        pub super_: &'a parent_::Parameters<'a>,
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    pub use crate::cli as parent_;
+    pub fn upload() {}
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command()]
+            pub struct Cli {
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                #[command()]
+                Upload {},
+            }
+        }
+        pub fn handle_matches(
+            cli: &cli::Cli,
+            super_parameters: &super::parent_::Parameters,
+        ) -> Option<u8> {
+            let parameters = super::Parameters {
+                super_: super_parameters,
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                Some(cli::Commands::Upload {}) => {
+                    super::upload();
+                    None
+                }
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+pub mod cli {
+    pub struct Parameters<'a> {
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    pub fn status() {}
+    /// Manage the cloud resources
+    pub use crate::cloud;
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command()]
+            pub struct Cli {
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum ExternalCommands {
+                /// Manage the cloud resources
+                #[command()]
+                Cloud(super::super::cloud::__internal_tusks_module::cli::Cli),
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                #[command()]
+                Status {},
+                #[command(flatten)]
+                TuskExternalCommands(ExternalCommands),
+            }
+        }
+        pub fn handle_matches(cli: &cli::Cli) -> Option<u8> {
+            let parameters = super::Parameters {
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                Some(cli::Commands::Status {}) => {
+                    super::status();
+                    None
+                }
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+                Some(cli::Commands::TuskExternalCommands(commands)) => {
+                    match commands {
+                        cli::ExternalCommands::Cloud(cli) => {
+                            super::cloud::__internal_tusks_module::handle_matches(
+                                cli,
+                                &parameters,
+                            )
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// Parse the command line arguments and run the selected command.
+    /// Returns the exit code of the command, if it has one.
+    pub fn exec_cli() -> Option<u8> {
+        let cli = {
+            use ::tusks::clap::Parser as _;
+            __internal_tusks_module::cli::Cli::parse()
+        };
+        __internal_tusks_module::handle_matches(&cli)
+    }
+}
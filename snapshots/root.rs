@@ -0,0 +1,67 @@
+pub mod cli {
+    pub struct Parameters<'a> {
+        pub verbose: &'a bool,
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    /// Deploy the application
+    pub fn deploy(params: &Parameters, region: Option<&str>) -> u8 {
+        0
+    }
+    pub fn status() {}
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command(name = "tool", version = "1.0")]
+            pub struct Cli {
+                #[arg(long)]
+                pub verbose: bool,
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                /// Deploy the application
+                #[command()]
+                Deploy { #[arg(long)] region: Option<String> },
+                #[command()]
+                Status {},
+            }
+        }
+        pub fn handle_matches(cli: &cli::Cli) -> Option<u8> {
+            let parameters = super::Parameters {
+                verbose: &cli.verbose,
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                Some(cli::Commands::Deploy { region: p1 }) => {
+                    Some(super::deploy(&parameters, (p1).as_deref()))
+                }
+                Some(cli::Commands::Status {}) => {
+                    super::status();
+                    None
+                }
+                None => {
+                    super::status();
+                    None
+                }
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+            }
+        }
+    }
+    /// Parse the command line arguments and run the selected command.
+    /// Returns the exit code of the command, if it has one.
+    pub fn exec_cli() -> Option<u8> {
+        let cli = {
+            use ::tusks::clap::Parser as _;
+            __internal_tusks_module::cli::Cli::parse()
+        };
+        __internal_tusks_module::handle_matches(&cli)
+    }
+}
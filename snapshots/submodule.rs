@@ -0,0 +1,108 @@
+pub mod cli {
+    pub struct Parameters<'a> {
+        pub verbose: &'a bool,
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    pub mod db {
+        pub struct Parameters<'a> {
+            pub url: &'a String,
+            pub super_: &'a super::Parameters<'a>,
+            pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+        }
+        pub fn run_migrations(params: &Parameters) -> Result<(), String> {
+            Ok(())
+        }
+    }
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command()]
+            pub struct Cli {
+                #[arg(long)]
+                pub verbose: bool,
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                #[command()]
+                #[command(name = "db")]
+                Db {
+                    #[arg(long)]
+                    url: String,
+                    #[command(subcommand)]
+                    sub: Option<db::Commands>,
+                },
+            }
+            pub mod db {
+                use ::tusks::clap;
+                #[derive(::tusks::clap::Subcommand)]
+                pub enum Commands {
+                    #[command()]
+                    #[command(name = "run_migrations")]
+                    RunMigrations {},
+                }
+            }
+        }
+        pub fn handle_matches(cli: &cli::Cli) -> Option<u8> {
+            let parameters = super::Parameters {
+                verbose: &cli.verbose,
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+                Some(cli::Commands::Db { url: p1, sub }) => {
+                    let super_parameters = &parameters;
+                    let parameters = super::db::Parameters {
+                        url: p1,
+                        super_: super_parameters,
+                        _phantom_lifetime_marker: ::std::marker::PhantomData,
+                    };
+                    match sub {
+                        Some(cli::db::Commands::RunMigrations {}) => {
+                            match super::db::run_migrations(&parameters) {
+                                Ok(value) => {
+                                    let _: () = value;
+                                    None
+                                }
+                                Err(error) => {
+                                    #[allow(unused_imports)]
+                                    use ::tusks::exit_code::{
+                                        CustomExitCode as _, DefaultExitCode as _,
+                                        DisplayMessage as _, ErrorChainMessage as _,
+                                    };
+                                    let report = ::tusks::exit_code::Report(&error);
+                                    eprintln!("Error: {}", (&& report).tusks_message());
+                                    Some((&&report).tusks_exit_code())
+                                }
+                            }
+                        }
+                        None => {
+                            eprintln!(
+                                "Subcommand required! Please provide a subcommand for {}!",
+                                "db"
+                            );
+                            Some(1)
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// Parse the command line arguments and run the selected command.
+    /// Returns the exit code of the command, if it has one.
+    pub fn exec_cli() -> Option<u8> {
+        let cli = {
+            use ::tusks::clap::Parser as _;
+            __internal_tusks_module::cli::Cli::parse()
+        };
+        __internal_tusks_module::handle_matches(&cli)
+    }
+}
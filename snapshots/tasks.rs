@@ -0,0 +1,239 @@
+pub mod tasks {
+    pub struct Parameters<'a> {
+        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+    }
+    use ::tusks::clap::{CommandFactory, Parser};
+    pub fn build() {}
+    pub mod db {
+        pub struct Parameters<'a> {
+            pub super_: &'a super::Parameters<'a>,
+            pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>,
+        }
+        pub fn migrate() {}
+    }
+    pub fn _execute_task(external_args: Vec<String>) -> Option<u8> {
+        let command = __internal_tusks_module::cli::Cli::command();
+        if let Some(first) = external_args.first() {
+            let mut transformed_arguments = vec![command.get_name().to_string()];
+            transformed_arguments.extend(first.split(".").map(|s| s.to_string()));
+            transformed_arguments.extend_from_slice(&external_args[1..]);
+            let cli = __internal_tusks_module::cli::Cli::parse_from(
+                transformed_arguments,
+            );
+            return __internal_tusks_module::handle_matches(&cli);
+        }
+        let task_list = ::tusks::tasks::task_list::models::TaskList::from_command(
+            &command,
+            ".".to_string(),
+            5usize,
+            20usize,
+        );
+        let mut render_config = ::tusks::tasks::list::models::RenderConfig::default();
+        render_config.use_colors = true;
+        task_list.to_list().print(&render_config);
+        Some(0)
+    }
+    pub fn _show_help_for_task(task: Option<String>) {
+        if let Some(task) = task {
+            let command = __internal_tusks_module::cli::Cli::command();
+            let parts: Vec<&str> = task.split(".").collect();
+            let args: Vec<&str> = std::iter::once(command.get_name())
+                .chain(parts.iter().copied())
+                .chain(std::iter::once("--help"))
+                .collect();
+            let cli = __internal_tusks_module::cli::Cli::parse_from(args);
+            __internal_tusks_module::handle_matches(&cli);
+        } else {
+            let command = __internal_tusks_module::cli::Cli::command();
+            let task_list = ::tusks::tasks::task_list::models::TaskList::from_command(
+                &command,
+                ".".to_string(),
+                5usize,
+                20usize,
+            );
+            task_list
+                .to_list()
+                .print(&::tusks::tasks::list::models::RenderConfig::default());
+        }
+    }
+    /// The shell code completing the first argument of `name` with the task names
+    #[doc(hidden)]
+    fn _task_completion_script(shell: &str, name: &str) -> Option<String> {
+        let function = format!("_{}_tasks", name.replace('-', "_"));
+        let script = match shell {
+            "bash" => {
+                format!(
+                    r#"
+{function}() {{
+    if [ "$COMP_CWORD" -eq 1 ] && [[ "${{COMP_WORDS[1]}}" != -* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($({name} __complete-tasks -- "${{COMP_WORDS[1]}}" 2>/dev/null))
+        return 0
+    fi
+    if declare -F _{name} >/dev/null; then
+        _{name} "$@"
+    fi
+}}
+complete -o bashdefault -o default -F {function} {name}
+"#
+                )
+            }
+            "zsh" => {
+                format!(
+                    r#"
+{function}() {{
+    if (( CURRENT == 2 )) && [[ "${{words[2]}}" != -* ]]; then
+        local -a tasks
+        tasks=(${{(f)"$({name} __complete-tasks -- "${{words[2]}}" 2>/dev/null)"}})
+        compadd -Q -a tasks
+    elif (( $+functions[_{name}] )); then
+        _{name} "$@"
+    else
+        _files
+    fi
+}}
+compdef {function} {name}
+"#
+                )
+            }
+            "fish" => {
+                format!(
+                    r#"
+complete -c {name} -f -n '__fish_is_first_arg; and not string match -q -- "-*" (commandline -ct)' -a '({name} __complete-tasks -- (commandline -ct))'
+"#
+                )
+            }
+            _ => return None,
+        };
+        Some(script)
+    }
+    pub fn _complete_task(prefix: Option<String>, shell: Option<String>) -> Option<u8> {
+        use ::std::io::Write as _;
+        let command = <__internal_tusks_module::cli::Cli as ::tusks::clap::CommandFactory>::command();
+        if let Some(shell) = shell {
+            let script = _task_completion_script(&shell, command.get_name())?;
+            return Some(
+                ::std::io::stdout().write_all(script.as_bytes()).map_or(1, |_| 0),
+            );
+        }
+        fn tree(command: &::tusks::clap::Command) -> ::tusks::task_names::TaskCommand {
+            ::tusks::task_names::TaskCommand {
+                name: command.get_name().to_string(),
+                hidden: command.is_hide_set(),
+                subcommands: command.get_subcommands().map(tree).collect(),
+            }
+        }
+        let names = ::tusks::task_names::task_names(&tree(&command), ".", 20usize);
+        let prefix = prefix.unwrap_or_default();
+        let mut output = String::new();
+        for name in names.iter().filter(|name| name.starts_with(&prefix)) {
+            output.push_str(name);
+            output.push('\n');
+        }
+        Some(::std::io::stdout().write_all(output.as_bytes()).map_or(1, |_| 0))
+    }
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub mod __internal_tusks_module {
+        pub mod cli {
+            use ::tusks::clap;
+            #[derive(::tusks::clap::Parser)]
+            #[command(allow_external_subcommands = true)]
+            pub struct Cli {
+                #[command(subcommand)]
+                pub sub: Option<Commands>,
+            }
+            #[derive(::tusks::clap::Subcommand)]
+            pub enum Commands {
+                #[command()]
+                Build {},
+                #[command(about = "Execute a task", hide = true)]
+                _ExecuteTask { #[arg(long)] external_args: Vec<String> },
+                #[command(about = "Show the help for a task", name = "h", hide = true)]
+                _ShowHelpForTask { #[arg()] task: Option<String> },
+                #[command(
+                    about = "Complete a task name",
+                    name = "__complete-tasks",
+                    hide = true
+                )]
+                _CompleteTask {
+                    #[arg()]
+                    prefix: Option<String>,
+                    #[arg(long, value_parser = ["bash", "zsh", "fish"])]
+                    shell: Option<String>,
+                },
+                #[command()]
+                Db { #[command(subcommand)] sub: Option<db::Commands> },
+                #[command(external_subcommand)]
+                ClapExternalSubcommand(Vec<String>),
+            }
+            pub mod db {
+                use ::tusks::clap;
+                #[derive(::tusks::clap::Subcommand)]
+                pub enum Commands {
+                    #[command()]
+                    Migrate {},
+                }
+            }
+        }
+        pub fn handle_matches(cli: &cli::Cli) -> Option<u8> {
+            let parameters = super::Parameters {
+                _phantom_lifetime_marker: ::std::marker::PhantomData,
+            };
+            let commands = &cli.sub;
+            match commands {
+                Some(cli::Commands::Build {}) => {
+                    super::build();
+                    None
+                }
+                Some(cli::Commands::_ExecuteTask { external_args: p1 }) => {
+                    super::_execute_task((p1).clone())
+                }
+                Some(cli::Commands::_ShowHelpForTask { task: p1 }) => {
+                    super::_show_help_for_task((p1).clone());
+                    None
+                }
+                Some(cli::Commands::_CompleteTask { prefix: p1, shell: p2 }) => {
+                    super::_complete_task((p1).clone(), (p2).clone())
+                }
+                None => super::_execute_task(Vec::new()),
+                Some(cli::Commands::ClapExternalSubcommand(external_subcommand_args)) => {
+                    super::_execute_task(external_subcommand_args.clone())
+                }
+                None => {
+                    eprintln!("Command required! Please provide a command!");
+                    Some(1)
+                }
+                Some(cli::Commands::Db { sub }) => {
+                    let super_parameters = &parameters;
+                    let parameters = super::db::Parameters {
+                        super_: super_parameters,
+                        _phantom_lifetime_marker: ::std::marker::PhantomData,
+                    };
+                    match sub {
+                        Some(cli::db::Commands::Migrate {}) => {
+                            super::db::migrate();
+                            None
+                        }
+                        None => {
+                            eprintln!(
+                                "Subcommand required! Please provide a subcommand for {}!",
+                                "db"
+                            );
+                            Some(1)
+                        }
+                    }
+                }
+            }
+        }
+    }
+    /// Parse the command line arguments and run the selected command.
+    /// Returns the exit code of the command, if it has one.
+    pub fn exec_cli() -> Option<u8> {
+        let cli = {
+            use ::tusks::clap::Parser as _;
+            __internal_tusks_module::cli::Cli::parse()
+        };
+        __internal_tusks_module::handle_matches(&cli)
+    }
+}
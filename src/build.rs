@@ -0,0 +1,180 @@
+//! Expansion of tusks modules from a build script, as an alternative to the proc-macro.
+//!
+//! The generated code is written to `OUT_DIR` once per change of the sources instead of being
+//! expanded on every compilation, and module files (`pub mod foo;`) are resolved relative to the
+//! given source file.
+//!
+//! # Example
+//! `build.rs`:
+//! ```ignore
+//! fn main() {
+//!     tusks_lib::build::generate("src/cli.rs").expect("failed to generate the cli");
+//! }
+//! ```
+//!
+//! `src/main.rs`, where `src/cli.rs` is not declared as a module:
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/cli.rs"));
+//!
+//! fn main() {
+//!     std::process::exit(cli::exec_cli().unwrap_or(0) as i32);
+//! }
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Item, ItemMod, Meta};
+
+use crate::attribute::models::TusksAttr;
+use crate::dump::pretty_print;
+use crate::expand::expand_inlined;
+use crate::file_modules::{InlinedFiles, inline_file_modules_in};
+
+/// Expands all tusks modules of `source_file` and writes the file with the expanded modules
+/// to `$OUT_DIR`, under the file name of `source_file`.
+///
+/// Emits `cargo:rerun-if-changed` for the source file and every module file read, also if the
+/// expansion fails, so that fixing a module file reruns the build script.
+///
+/// # Errors
+/// Fails if `OUT_DIR` is not set, a file cannot be read or written, or the tusks modules contain
+/// errors. The message of an error in a tusks module lists every error with its position.
+pub fn generate(source_file: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| io::Error::new(
+        io::ErrorKind::NotFound,
+        "OUT_DIR is not set, tusks_lib::build::generate must be called from a build script"
+    ))?;
+
+    generate_to(source_file, out_dir)
+}
+
+/// Like `generate`, but writes to `out_dir` instead of `$OUT_DIR`
+pub fn generate_to(source_file: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let source_file = source_file.as_ref();
+    println!("cargo:rerun-if-changed={}", source_file.display());
+
+    let mut files = InlinedFiles::default();
+    let expansion = expand_source_file(source_file, &mut files);
+    for file in files.paths() {
+        println!("cargo:rerun-if-changed={}", file.display());
+    }
+    let expansion = expansion?;
+
+    let file_name = source_file.file_name().unwrap_or("tusks.rs".as_ref());
+    let out_file = out_dir.as_ref().join(file_name);
+    std::fs::create_dir_all(out_dir.as_ref())?;
+    std::fs::write(&out_file, pretty_print(&expansion))?;
+
+    Ok(out_file)
+}
+
+/// Expands all modules annotated with `#[tusks(...)]` at the top level of `source_file`, the
+/// other items are kept as they are. Inner attributes of the file (e.g. `//!` docs) are left out,
+/// as they are not allowed in a file included via `include!`.
+///
+/// Returns the expanded file and the module files that were read.
+pub fn expand_file(source_file: &Path) -> io::Result<(TokenStream, Vec<PathBuf>)> {
+    let mut files = InlinedFiles::default();
+    let expansion = expand_source_file(source_file, &mut files)?;
+    Ok((expansion, files.paths().map(Path::to_path_buf).collect()))
+}
+
+/// `expand_file`, collecting the module files read into `files` also if the expansion fails
+fn expand_source_file(source_file: &Path, files: &mut InlinedFiles) -> io::Result<TokenStream> {
+    // Module files are embedded via `include_str!`, which needs absolute paths
+    let source_file = &std::path::absolute(source_file)?;
+    let source = std::fs::read_to_string(source_file)?;
+    let file = syn::parse_file(&source).map_err(|e| to_io_error(source_file, files, e))?;
+
+    let mut items = Vec::new();
+
+    for item in file.items {
+        match item {
+            Item::Mod(module) if tusks_attribute(&module.attrs).is_some() => {
+                let expansion = expand_module(module, source_file, files)
+                    .map_err(|e| to_io_error(source_file, files, e))?;
+                items.push(expansion);
+            }
+            other => items.push(quote! { #other }),
+        }
+    }
+
+    Ok(quote! { #(#items)* })
+}
+
+/// Inline the module files of a tusks module and expand it. The files are inlined here only,
+/// so that the errors inside them keep their positions.
+fn expand_module(
+    mut module: ItemMod,
+    source_file: &Path,
+    files: &mut InlinedFiles
+) -> syn::Result<TokenStream> {
+    let Some(index) = tusks_attribute(&module.attrs) else {
+        return Ok(quote! { #module });
+    };
+    let attr = module.attrs.remove(index);
+
+    let tusks_attr: TusksAttr = match attr.meta {
+        Meta::List(list) => syn::parse2(list.tokens)?,
+        _ => syn::parse2(TokenStream::new())?,
+    };
+
+    inline_file_modules_in(&mut module, source_file, files)?;
+    expand_inlined(tusks_attr, module)
+}
+
+/// Index of the `#[tusks(...)]` (or `#[tusks::tusks(...)]`) attribute
+fn tusks_attribute(attrs: &[Attribute]) -> Option<usize> {
+    attrs.iter().position(|attr| {
+        attr.path().segments.last().is_some_and(|segment| segment.ident == "tusks")
+    })
+}
+
+/// Turn the (combined) errors into one message with a `file:line:column` position per error,
+/// in the module file the error points into or else in `source_file`
+fn to_io_error(source_file: &Path, files: &InlinedFiles, error: syn::Error) -> io::Error {
+    let messages: Vec<String> = error.into_iter().map(|error| {
+        let file = files.located_file(error.span()).unwrap_or(source_file);
+        let start = error.span().start();
+        format!("{}:{}:{}: {}", file.display(), start.line, start.column + 1, error)
+    }).collect();
+
+    io::Error::new(io::ErrorKind::InvalidData, messages.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_file_with_module_files() {
+        let dir = std::env::temp_dir().join(format!("tusks_build_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("cli").join("deploy")).unwrap();
+        let main = "#[tusks(root)]\npub mod cli {\n    pub mod deploy;\n}\n";
+        std::fs::write(dir.join("main.rs"), main).unwrap();
+        std::fs::write(dir.join("cli").join("deploy.rs"), "pub mod aws;\n").unwrap();
+        let aws = dir.join("cli").join("deploy").join("aws.rs");
+        std::fs::write(&aws, "pub fn upload() {}\n").unwrap();
+
+        let (expansion, read_files) = expand_file(&dir.join("main.rs")).unwrap();
+        assert_eq!(read_files, [dir.join("cli").join("deploy.rs"), aws.clone()]);
+        assert!(pretty_print(&expansion).contains("pub fn exec_cli()"));
+
+        // An error inside a module file points to that file, which is read once
+        std::fs::write(&aws, "pub fn upload() {}\n\npub fn list() -> String { String::new() }\n")
+            .unwrap();
+        let mut files = InlinedFiles::default();
+        let error = expand_source_file(&dir.join("main.rs"), &mut files).unwrap_err();
+        assert!(
+            error.to_string().starts_with(&format!("{}:3:", aws.display())),
+            "unexpected error: {}",
+            error
+        );
+        assert_eq!(files.paths().collect::<Vec<_>>(), [dir.join("cli").join("deploy.rs"), aws]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
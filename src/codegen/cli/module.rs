@@ -2,13 +2,18 @@ use quote::quote;
 use proc_macro2::TokenStream;
 use syn::Ident;
 
+use crate::codegen::util::attribute::{extract_cfg_attributes, is_named_attribute};
+use crate::codegen::util::command_attribute::generate_command_name_attribute;
+use crate::codegen::util::type_mapping::owned_type;
+use crate::codegen::util::warning::build_warning;
+use crate::parsing::util::doc_comment::parse_arguments_section;
 use crate::codegen::util::enum_util::{
     convert_external_module_to_enum_variant,
     convert_function_to_enum_variant,
     convert_submodule_to_enum_variant
 };
 
-use crate::{TusksModule, models::{Tusk, TusksParameters}};
+use crate::{TusksModule, models::{StdImports, Tusk, TusksParameters}};
 
 impl TusksModule{
     /// Generate all code inside `pub mod cli`
@@ -16,6 +21,7 @@ impl TusksModule{
         let mut items = Vec::new();
 
         items.push(quote! {use ::tusks::clap;});
+        items.push(self.build_parse_warnings());
         
         // 1. If root (path empty): generate Cli struct
         if path.is_empty() {
@@ -28,8 +34,9 @@ impl TusksModule{
         }
         
         // 3. Generate Commands enum if needed
-        if !self.tusks.is_empty() || !self.external_modules.is_empty() {
+        if self.has_commands() {
             items.push(self.build_commands_enum(debug));
+            items.push(self.build_argument_doc_warnings());
         }
         
         // 4. Generate submodule modules and recurse
@@ -38,8 +45,10 @@ impl TusksModule{
             sub_path.push(&submodule.name);
             let submod_name = &submodule.name;
             let submod_content = submodule.build_cli(sub_path, debug);
+            let cfg_attrs = submodule.cfg_attributes();
             
             items.push(quote! {
+                #(#cfg_attrs)*
                 pub mod #submod_name {
                     #submod_content
                 }
@@ -61,7 +70,7 @@ impl TusksModule{
         };
         
         // Add subcommand field if we have commands
-        let subcommand_field = if !self.tusks.is_empty() || !self.external_modules.is_empty() {
+        let subcommand_field = if self.has_commands() {
             let subcommand_attr = self.generate_command_attribute_for_subcommands();
             quote! {
                 #subcommand_attr
@@ -78,12 +87,24 @@ impl TusksModule{
         };
         
         let command_attr = self.generate_command_attribute();
+
+        // Only read before parsing, see tusks_lib::config
+        let config_field = if self.config_file.is_some() {
+            quote! {
+                /// Read the defaults from this file on top of the user and project config files
+                #[arg(long = "config", value_name = "PATH", global = true)]
+                pub tusks_config_file: Option<::std::path::PathBuf>,
+            }
+        } else {
+            quote! {}
+        };
         
         quote! {
             #derive_attr
             #command_attr
             pub struct Cli {
                 #fields
+                #config_field
                 #subcommand_field
             }
         }
@@ -106,15 +127,20 @@ impl TusksModule{
                 continue;
             }
 
-            let field_type = Self::dereference_type(&field.ty);
+            let field_type = params.cli_field_type(field, &self.std_imports);
+            let cfg_attrs = extract_cfg_attributes(&field.attrs);
 
-            // Filter and keep #[arg(...)] attributes with original spans
+            // Filter and keep #[arg(...)] attributes (also behind #[cfg_attr]) with original spans
             let attrs: Vec<_> = field.attrs.iter()
-                .filter(|attr| attr.path().is_ident("arg"))
+                .filter(|attr| attr.path().is_ident("arg") || is_named_attribute(attr, &["arg"]))
                 .collect();
 
+            let env_attr = TusksParameters::build_env_attribute(field, self.env_prefix.as_deref());
+
             fields.push(quote! {
+                #(#cfg_attrs)*
                 #(#attrs)*
+                #env_attr
                 pub #field_name: #field_type,
             });
         }
@@ -124,16 +150,6 @@ impl TusksModule{
         }
     }
     
-    /// Convert a reference type to its dereferenced type
-    /// e.g., &Option<String> -> Option<String>
-    fn dereference_type(ty: &syn::Type) -> syn::Type {
-        if let syn::Type::Reference(type_ref) = ty {
-            (*type_ref.elem).clone()
-        } else {
-            ty.clone()
-        }
-    }
-    
     /// Generate the ExternalCommands enum
     fn build_external_commands_enum(&self, path: &Vec<&Ident>, debug: bool) -> TokenStream {
         let variants: Vec<_> = self.external_modules.iter().map(|ext_mod| {
@@ -153,10 +169,17 @@ impl TusksModule{
             full_path.push(ext_mod.alias.clone());
 
             let command_attr = ext_mod.generate_command_attribute();
+            let name_attr = generate_command_name_attribute(
+                &ext_mod.alias,
+                self.rename_all,
+                &ext_mod.extract_attributes(&["command"])
+            );
+            let cfg_attrs = ext_mod.cfg_attributes();
             
             quote! {
+                #(#cfg_attrs)*
                 #command_attr
-                #[allow(non_camel_case_types)]
+                #name_attr
                 #variant_ident(
                     #(#full_path)::*::__internal_tusks_module::cli::Cli
                 ),
@@ -229,10 +252,17 @@ impl TusksModule{
         let fields = self.build_fields_from_tusk_params(tusk);
 
         let command_attr = tusk.generate_command_attribute();
+        let name_attr = generate_command_name_attribute(
+            func_name,
+            self.rename_all,
+            &tusk.extract_attributes(&["command"])
+        );
+        let cfg_attrs = tusk.cfg_attributes();
         
         quote! {
+            #(#cfg_attrs)*
             #command_attr
-            #[allow(non_camel_case_types)]
+            #name_attr
             #variant_ident {
                 #fields
             },
@@ -240,8 +270,11 @@ impl TusksModule{
     }
 
     /// Build fields from tusk function parameters
+    /// Arguments documented in the `# Arguments` section of the function docs
+    /// get their description as help text.
     fn build_fields_from_tusk_params(&self, tusk: &Tusk) -> TokenStream {
         let mut fields = Vec::new();
+        let argument_docs = parse_arguments_section(&tusk.func.attrs);
 
         let mut params_iter = tusk.func.sig.inputs.iter();
 
@@ -272,20 +305,31 @@ impl TusksModule{
                     continue;
                 };
 
-                let param_type = &pat_type.ty;
+                // Borrowed argument types become owned clap fields, e.g. &str -> String
+                let param_type = owned_type(&pat_type.ty, &self.std_imports);
 
-                // Filter #[arg(...)] attributes
+                // clap uses the doc comment of a field as its help
+                let help = argument_docs.iter()
+                    .find(|doc| *param_name == doc.name)
+                    .map(|doc| {
+                        let help = syn::LitStr::new(&doc.help, doc.span);
+                        quote! { #[doc = #help] }
+                    });
+
+                // Filter #[arg(...)] attributes (also behind #[cfg_attr])
                 let attrs: Vec<_> = pat_type.attrs.iter()
-                    .filter(|attr| attr.path().is_ident("arg"))
+                    .filter(|attr| attr.path().is_ident("arg") || is_named_attribute(attr, &["arg"]))
                     .collect();
 
                 if !attrs.is_empty() {
                     fields.push(quote! {
+                        #help
                         #(#attrs)*
                         #param_name: #param_type,
                     });
                 } else {
                     fields.push(quote! {
+                        #help
                         #[arg(long)]
                         #param_name: #param_type,
                     });
@@ -298,6 +342,42 @@ impl TusksModule{
         }
     }
 
+    /// Generate the warnings found while parsing the module
+    fn build_parse_warnings(&self) -> TokenStream {
+        let warnings = self.warnings.iter().map(|(span, message)| build_warning(*span, message));
+        quote! { #(#warnings)* }
+    }
+
+    /// Warn about entries in the `# Arguments` doc sections of the tusks which do not name
+    /// an argument of the function
+    fn build_argument_doc_warnings(&self) -> TokenStream {
+        let mut warnings = Vec::new();
+
+        for tusk in &self.tusks {
+            for doc in parse_arguments_section(&tusk.func.attrs) {
+                let exists = tusk.func.sig.inputs.iter().any(|input| {
+                    matches!(
+                        input,
+                        syn::FnArg::Typed(pat_type) if matches!(
+                            &*pat_type.pat,
+                            syn::Pat::Ident(pat_ident) if pat_ident.ident == doc.name
+                        )
+                    )
+                });
+
+                if !exists {
+                    warnings.push(build_warning(doc.span, &format!(
+                        "documented argument `{}` does not exist in function `{}`",
+                        doc.name,
+                        tusk.func.sig.ident
+                    )));
+                }
+            }
+        }
+
+        quote! { #(#warnings)* }
+    }
+
     /// Check if a type is a reference to a parameters struct
     pub fn is_parameters_type(ty: &syn::Type, params_ident: &Ident) -> bool {
         if let syn::Type::Reference(type_ref) = ty {
@@ -317,13 +397,17 @@ impl TusksModule{
 
         // Extract fields from submodule's parameters
         let fields = if let Some(ref params) = submodule.parameters {
-            self.build_enum_fields_from_parameters(params)
+            self.build_enum_fields_from_parameters(
+                params,
+                submodule.env_prefix.as_deref(),
+                &submodule.std_imports
+            )
         } else {
             quote! {}
         };
 
         // Add subcommand field if submodule has commands
-        let subcommand_field = if !submodule.tusks.is_empty() || !submodule.external_modules.is_empty() {
+        let subcommand_field = if submodule.has_commands() {
             let subcommand_attr = submodule.generate_command_attribute_for_subcommands();
             quote! {
                 #subcommand_attr
@@ -334,10 +418,17 @@ impl TusksModule{
         };
 
         let command_attr = submodule.generate_command_attribute();
+        let name_attr = generate_command_name_attribute(
+            submod_name,
+            self.rename_all,
+            &submodule.extract_attributes(&["command"])
+        );
+        let cfg_attrs = submodule.cfg_attributes();
         
         quote! {
+            #(#cfg_attrs)*
             #command_attr
-            #[allow(non_camel_case_types)]
+            #name_attr
             #variant_ident {
                 #fields
                 #subcommand_field
@@ -346,7 +437,14 @@ impl TusksModule{
     }
 
     /// Build fields for enum variants from Parameters struct (without pub)
-    fn build_enum_fields_from_parameters(&self, params: &TusksParameters) -> TokenStream {
+    /// The fields of the variant of a submodule; `self` is the parent module, `env_prefix` and
+    /// `std_imports` are those of the submodule
+    fn build_enum_fields_from_parameters(
+        &self,
+        params: &TusksParameters,
+        env_prefix: Option<&str>,
+        std_imports: &StdImports
+    ) -> TokenStream {
         let mut fields = Vec::new();
 
         for field in &params.pstruct.fields {
@@ -361,15 +459,20 @@ impl TusksModule{
                 continue;
             }
 
-            let field_type = Self::dereference_type(&field.ty);
+            let field_type = params.cli_field_type(field, std_imports);
+            let cfg_attrs = extract_cfg_attributes(&field.attrs);
 
-            // Filter and keep #[arg(...)] attributes with original spans
+            // Filter and keep #[arg(...)] attributes (also behind #[cfg_attr]) with original spans
             let attrs: Vec<_> = field.attrs.iter()
-                .filter(|attr| attr.path().is_ident("arg"))
+                .filter(|attr| attr.path().is_ident("arg") || is_named_attribute(attr, &["arg"]))
                 .collect();
 
+            let env_attr = TusksParameters::build_env_attribute(field, env_prefix);
+
             fields.push(quote! {
+                #(#cfg_attrs)*
                 #(#attrs)*
+                #env_attr
                 #field_name: #field_type,
             });
         }
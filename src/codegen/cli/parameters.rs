@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Field};
+
+use crate::attribute::models::RenameRule;
+use crate::codegen::util::arg_attribute::arg_arguments;
+use crate::codegen::util::enum_util::ident_name;
+use crate::models::TusksParameters;
+
+impl TusksParameters {
+    /// Generate `#[arg(env = "...")]` for a Parameters field, named after the env prefix of the
+    /// module and the field. Nothing is generated without a prefix, or if the field sets `env`
+    /// itself (or is skipped by clap).
+    ///
+    /// # Example
+    /// For `env_prefix` = `MYTOOL_DEPLOY` and the field `region`:
+    /// `#[arg(env = "MYTOOL_DEPLOY_REGION")]`
+    pub fn build_env_attribute(field: &Field, env_prefix: Option<&str>) -> TokenStream {
+        let (Some(env_prefix), Some(field_name)) = (env_prefix, &field.ident) else {
+            return quote! {};
+        };
+
+        if has_env_or_skip(&field.attrs) {
+            return quote! {};
+        }
+
+        let env_name = format!(
+            "{}_{}",
+            env_prefix,
+            RenameRule::ScreamingSnakeCase.apply(&ident_name(field_name))
+        );
+
+        quote! { #[arg(env = #env_name)] }
+    }
+}
+
+/// Checks if one of the `#[arg(...)]` attributes contains `env`, `env = "..."` or `skip`
+fn has_env_or_skip(attrs: &[Attribute]) -> bool {
+    arg_arguments(attrs).iter().any(|meta| meta.path().is_ident("env") || meta.path().is_ident("skip"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ItemStruct, parse_quote};
+
+    fn field(item_struct: ItemStruct) -> Field {
+        item_struct.fields.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_build_env_attribute() {
+        let env = |item_struct: ItemStruct, env_prefix| {
+            TusksParameters::build_env_attribute(&field(item_struct), env_prefix).to_string()
+        };
+
+        let dry_run: ItemStruct = parse_quote! { pub struct Parameters<'a> { pub dry_run: &'a bool } };
+        assert_eq!(env(dry_run.clone(), Some("MYTOOL")), "# [arg (env = \"MYTOOL_DRY_RUN\")]");
+        assert_eq!(env(dry_run, None), "");
+
+        let raw: ItemStruct = parse_quote! { pub struct Parameters<'a> { pub r#type: &'a String } };
+        assert_eq!(env(raw, Some("MY_TOOL")), "# [arg (env = \"MY_TOOL_TYPE\")]");
+    }
+
+    #[test]
+    fn test_has_env_or_skip() {
+        let has_env_or_skip = |item_struct: ItemStruct| {
+            super::has_env_or_skip(&field(item_struct).attrs)
+        };
+
+        assert!(has_env_or_skip(parse_quote! {
+            pub struct Parameters<'a> { #[arg(long, env)] pub region: &'a String }
+        }));
+        assert!(has_env_or_skip(parse_quote! {
+            pub struct Parameters<'a> { #[arg(long, env = "REGION")] pub region: &'a String }
+        }));
+        assert!(has_env_or_skip(parse_quote! {
+            pub struct Parameters { #[arg(skip)] pub cache: Vec<String> }
+        }));
+        assert!(!has_env_or_skip(parse_quote! {
+            pub struct Parameters<'a> {
+                #[arg(long, default_value = "env")]
+                pub region: &'a String
+            }
+        }));
+        assert!(!has_env_or_skip(parse_quote! {
+            pub struct Parameters<'a> { #[command(env)] pub region: &'a String }
+        }));
+    }
+}
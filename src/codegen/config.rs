@@ -0,0 +1,223 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use syn::{Attribute, Field};
+
+use crate::TusksModule;
+use crate::codegen::util::arg_attribute::arg_id;
+use crate::codegen::util::attribute::extract_cfg_attributes;
+use crate::codegen::util::command_attribute::command_name;
+use crate::codegen::util::enum_util::ident_name;
+
+impl TusksModule {
+    /// Generate `mod config` of a root module with `#[tusks(config_file = "...")]`. Its
+    /// `parse_cli` replaces `Cli::parse` and turns the values of the config files into the
+    /// default values of the Parameters arguments, so they rank between the defaults of clap and
+    /// the values given via environment variables or the command line. Reading and merging the
+    /// files is up to `tusks_lib::config`, which documents their order.
+    ///
+    /// # Example
+    /// The sections of the file mirror the module tree, the keys are the Parameters fields:
+    /// ```toml
+    /// verbose = true          # cli::Parameters::verbose
+    ///
+    /// [deploy]
+    /// region = "eu-west-1"    # cli::deploy::Parameters::region
+    /// ```
+    /// Keys of skipped fields, of external modules or of nothing at all are rejected.
+    pub fn build_config(&self) -> TokenStream {
+        let Some(ref file_name) = self.config_file else {
+            return quote! {};
+        };
+
+        let mut key_arms = Vec::new();
+        self.collect_config_keys("", &[], &mut key_arms);
+        let apply_defaults = self.build_config_defaults();
+
+        quote! {
+            pub mod config {
+                use ::tusks::clap::{Command, CommandFactory as _, FromArgMatches as _};
+                use ::tusks::config::Key;
+                use ::tusks::toml::{Table, Value};
+                use ::std::ffi::OsString;
+
+                const FILE_NAME: &str = #file_name;
+
+                /// Parse the command line with the values of the config files as defaults
+                pub fn parse_cli() -> super::cli::Cli {
+                    let args: Vec<OsString> = ::std::env::args_os().collect();
+                    let config = ::tusks::config::load(FILE_NAME, &args, key).unwrap_or_else(|error| {
+                        eprintln!("error: {}", error);
+                        ::std::process::exit(2)
+                    });
+                    let command = apply_defaults(super::cli::Cli::command(), &config);
+                    let matches = command.get_matches_from(args);
+                    super::cli::Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit())
+                }
+
+                /// What the key at the dotted `path` of the config file configures
+                #[allow(unreachable_patterns)]
+                fn key(path: &str) -> Option<Key> {
+                    match path {
+                        #(#key_arms)*
+                        _ => None,
+                    }
+                }
+
+                fn set_default(command: Command, id: &'static str, value: &Value) -> Command {
+                    let values = ::tusks::config::default_values(value);
+                    // clap does not count default values as present, the value of the config
+                    // satisfies a required argument though
+                    command.mut_arg(id, |arg| arg.default_values(values).required(false))
+                }
+
+                fn apply_defaults(command: Command, config: &Table) -> Command {
+                    #apply_defaults
+                    command
+                }
+            }
+        }
+    }
+
+    /// Match arms of the generated `config::key` for the keys of this module and its submodules,
+    /// below the dotted path `prefix`
+    ///
+    /// # Example
+    /// For the field `region` of the submodule `deploy`:
+    /// ```ignore
+    /// "deploy" => Some(Key::Section),
+    /// "deploy.region" => Some(Key::Value),
+    /// ```
+    fn collect_config_keys(&self, prefix: &str, cfg_attrs: &[Attribute], arms: &mut Vec<TokenStream>) {
+        for (field, _) in self.config_fields() {
+            let path = format!("{}{}", prefix, field.ident.as_ref().map(ident_name).unwrap_or_default());
+            let field_cfg_attrs = extract_cfg_attributes(&field.attrs);
+            arms.push(quote! {
+                #(#cfg_attrs)* #(#field_cfg_attrs)*
+                #path => Some(Key::Value),
+            });
+        }
+
+        for submodule in &self.submodules {
+            let path = format!("{}{}", prefix, ident_name(&submodule.name));
+            let mut submodule_cfg_attrs = cfg_attrs.to_vec();
+            submodule_cfg_attrs.extend(submodule.cfg_attributes().into_iter().cloned());
+            arms.push(quote! {
+                #(#submodule_cfg_attrs)*
+                #path => Some(Key::Section),
+            });
+            submodule.collect_config_keys(&format!("{}.", path), &submodule_cfg_attrs, arms);
+        }
+    }
+
+    /// Statements setting the defaults of the arguments of this module and its submodules from
+    /// the table `config`, shadowing `command`
+    fn build_config_defaults(&self) -> TokenStream {
+        let mut statements = Vec::new();
+
+        for (field, arg_id) in self.config_fields() {
+            let field_name = field.ident.as_ref().map(ident_name).unwrap_or_default();
+            let cfg_attrs = extract_cfg_attributes(&field.attrs);
+            statements.push(quote! {
+                #(#cfg_attrs)*
+                let command = match config.get(#field_name) {
+                    Some(value) => set_default(command, #arg_id, value),
+                    None => command,
+                };
+            });
+        }
+
+        for submodule in &self.submodules {
+            let section = ident_name(&submodule.name);
+            let name = command_name(
+                &submodule.name,
+                self.rename_all,
+                &submodule.extract_attributes(&["command"])
+            );
+            let cfg_attrs = submodule.cfg_attributes();
+            let submodule_defaults = submodule.build_config_defaults();
+
+            statements.push(quote! {
+                #(#cfg_attrs)*
+                let command = match config.get(#section) {
+                    Some(Value::Table(config)) => command.mut_subcommand(#name, |command| {
+                        #submodule_defaults
+                        command
+                    }),
+                    _ => command,
+                };
+            });
+        }
+
+        quote! { #(#statements)* }
+    }
+
+    /// The Parameters fields that can be set in the config file, with the id of their argument.
+    /// Skipped and flattened fields have no argument of their own and are left out.
+    fn config_fields(&self) -> Vec<(&Field, String)> {
+        let Some(ref params) = self.parameters else {
+            return Vec::new();
+        };
+
+        params.pstruct.fields.iter()
+            .filter(|field| field.ident.as_ref().is_some_and(|ident| {
+                ident != "super_" && ident != "_phantom_lifetime_marker"
+            }))
+            .filter_map(|field| arg_id(field).map(|arg_id| (field, arg_id)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn config(module: syn::ItemMod) -> String {
+        let mut tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        tusks_module.config_file = Some("tool.toml".to_string());
+        tusks_module.build_config().to_string()
+    }
+
+    #[test]
+    fn test_keys_map_to_arg_ids() {
+        let config = config(parse_quote! {
+            pub mod tasks {
+                pub struct Parameters<'a> {
+                    #[arg(long, id = "target")]
+                    pub region: &'a String,
+                    #[arg(skip)]
+                    pub cache: Vec<String>,
+                }
+                #[command(name = "ship")]
+                pub mod deploy {
+                    pub struct Parameters<'a> {
+                        #[cfg(feature = "cloud")]
+                        #[arg(long)]
+                        pub r#type: &'a String,
+                    }
+                    pub fn run() {}
+                }
+            }
+        });
+
+        assert!(config.contains(
+            "match config . get (\"region\") { Some (value) => set_default (command , \"target\" , value)"
+        ));
+        assert!(config.contains(
+            "match config . get (\"deploy\") { Some (Value :: Table (config)) => command . mut_subcommand (\"ship\""
+        ));
+        assert!(config.contains(
+            "# [cfg (feature = \"cloud\")] let command = match config . get (\"type\") \
+                { Some (value) => set_default (command , \"type\" , value)"
+        ));
+        assert!(!config.contains("cache"));
+
+        assert!(config.contains(concat!(
+            "match path { \"region\" => Some (Key :: Value) , ",
+            "\"deploy\" => Some (Key :: Section) , ",
+            "# [cfg (feature = \"cloud\")] \"deploy.type\" => Some (Key :: Value) , ",
+            "_ => None , }"
+        )));
+    }
+}
@@ -0,0 +1,113 @@
+//! Writing the expansion of a tusks module to a file, to read the generated code and to diff it
+//! across versions.
+//!
+//! The file is chosen by `#[tusks(expand_to = "...")]`, relative to the crate root, or else by the
+//! `TUSKS_EXPAND_DIR` environment variable, which gets one `<crate>.<module>.rs` file per module.
+//! The environment variable is read while the macro expands, so changing it requires a rebuild
+//! of the crate (e.g. `touch src/main.rs`).
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use syn::Ident;
+
+use crate::attribute::models::TusksAttr;
+use crate::codegen::util::warning::build_warning;
+
+/// Environment variable naming the directory all expansions are written to
+pub const EXPAND_DIR_VAR: &str = "TUSKS_EXPAND_DIR";
+
+/// Formats the tokens like `rustfmt` would. Tokens which do not form a valid file are returned
+/// as a plain token string.
+pub fn pretty_print(tokens: &TokenStream) -> String {
+    match syn::parse2::<syn::File>(tokens.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => tokens.to_string(),
+    }
+}
+
+/// Writes the pretty-printed expansion of `module` if it was requested by `expand_to` or
+/// `TUSKS_EXPAND_DIR`.
+///
+/// The file only serves reading the generated code, so a failure to write it does not fail the
+/// expansion: the returned tokens hold a warning pointing to `expand_to` (or to the module)
+/// then, and are empty otherwise.
+pub fn dump_expansion(
+    tusks_attr: &TusksAttr,
+    module: &Ident,
+    expansion: &TokenStream
+) -> TokenStream {
+    let Some(path) = expansion_path(tusks_attr, module) else {
+        return TokenStream::new();
+    };
+
+    match write_expansion(&path, expansion) {
+        Ok(()) => TokenStream::new(),
+        Err(error) => {
+            let message = format!("failed to write the expansion to {}: {}", path.display(), error);
+            let span = match tusks_attr.expand_to {
+                Some(ref expand_to) => expand_to.span(),
+                None => module.span(),
+            };
+            build_warning(span, &message)
+        }
+    }
+}
+
+/// Writes the pretty-printed tokens to `path`, creating the missing directories
+pub fn write_expansion(path: &Path, expansion: &TokenStream) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, pretty_print(expansion))
+}
+
+fn expansion_path(tusks_attr: &TusksAttr, module: &Ident) -> Option<PathBuf> {
+    if let Some(ref expand_to) = tusks_attr.expand_to {
+        return Some(relative_to_crate_root(PathBuf::from(expand_to.value())));
+    }
+
+    let dir = std::env::var_os(EXPAND_DIR_VAR).filter(|dir| !dir.is_empty())?;
+    let file_name = match std::env::var("CARGO_CRATE_NAME") {
+        Ok(crate_name) => format!("{}.{}.rs", crate_name, module),
+        Err(_) => format!("{}.rs", module),
+    };
+
+    Some(relative_to_crate_root(PathBuf::from(dir)).join(file_name))
+}
+
+/// Relative paths are resolved against `CARGO_MANIFEST_DIR`, as the working directory of the
+/// compiler is not necessarily the crate root (e.g. in workspaces)
+fn relative_to_crate_root(path: PathBuf) -> PathBuf {
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) if path.is_relative() => Path::new(&manifest_dir).join(path),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn test_pretty_print() {
+        let tokens = quote! { pub mod cli { pub fn deploy() {} } };
+        assert_eq!(pretty_print(&tokens), "pub mod cli {\n    pub fn deploy() {}\n}\n");
+    }
+
+    #[test]
+    fn test_dump_failure_is_a_warning() {
+        let tusks_attr: TusksAttr = syn::parse_quote! { expand_to = "/dev/null/cli.rs" };
+        let module: Ident = syn::parse_quote! { cli };
+
+        let warning = dump_expansion(&tusks_attr, &module, &quote! { pub mod cli {} }).to_string();
+        assert!(warning.contains("deprecated"));
+        assert!(warning.contains("failed to write the expansion to /dev/null/cli.rs"));
+
+        let tusks_attr: TusksAttr = syn::parse_quote! { root };
+        assert!(dump_expansion(&tusks_attr, &module, &quote! { pub mod cli {} }).is_empty());
+    }
+}
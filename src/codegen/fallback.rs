@@ -0,0 +1,328 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Fields, FnArg, Ident, Item, ItemMod, ItemStruct, Lifetime, Type, UseTree,
+    parse_quote
+};
+
+use crate::codegen::util::attribute::cfg_attr_arguments;
+use crate::TusksModule;
+use crate::models::TusksParameters;
+
+/// Attributes which only tusks understands. Nothing else consumes them, so they are removed from
+/// the items of the module once the code is generated, and in the fallback expansion.
+const TUSKS_ATTRIBUTES: &[&str] = &[
+    "tusks",
+    "default",
+    "skip",
+    "command",
+    "tusk",
+    "parameters",
+    "arg",
+    "subcommands",
+    "external_subcommands",
+];
+
+/// Builds the expansion used when the tusks module contains errors: the module with its items
+/// unchanged, except for the tusks-only attributes, followed by a `compile_error!` for every
+/// error. The code of the module keeps type-checking, so IDEs still offer completion and
+/// navigation inside it while the errors are fixed. The parameters structs get what the
+/// commands rely on (see `add_parameters_stand_ins`), so only the actual errors are reported.
+///
+/// # Example
+/// For a module with an invalid return type:
+/// ```ignore
+/// pub mod cli {
+///     pub fn deploy() -> String { ... } // #[default] removed
+/// }
+/// ::core::compile_error! { "command function must return (), u8, Option<u8>, ..." }
+/// ```
+pub fn fallback_expansion(module: &ItemMod, error: syn::Error) -> TokenStream {
+    let mut module = module.clone();
+    add_parameters_stand_ins(&mut module, None);
+    remove_tusks_attributes_from_module(&mut module);
+    let compile_errors = error.into_compile_error();
+
+    quote! {
+        #module
+        #compile_errors
+    }
+}
+
+/// Give the parameters struct of the module and its submodules what the expansion would add:
+/// the lifetime, the `super_` field, the phantom lifetime marker and the `Parameters` alias of a
+/// struct with another name. A module without parameters struct gets an empty `Parameters`.
+/// Commands using `&Parameters` or `params.super_` then compile as they would after the
+/// expansion.
+///
+/// `parent_parameters` is the name of the parameters struct of the parent module, `None` for
+/// the tusks module itself, whose `super_` points to `parent_` if it declares one.
+fn add_parameters_stand_ins(module: &mut ItemMod, parent_parameters: Option<&Ident>) {
+    let Some((_, ref mut items)) = module.content else {
+        return;
+    };
+
+    let parameters_ident = TusksParameters::find_name(items);
+    let super_type: Option<Type> = match parent_parameters {
+        Some(parent_parameters) => Some(parse_quote! { &'a super::#parent_parameters<'a> }),
+        None if items.iter().any(|item| matches!(item, Item::Use(item_use) if declares_parent(&item_use.tree))) => {
+            Some(parse_quote! { &'a parent_::Parameters<'a> })
+        }
+        None => None,
+    };
+
+    let parameters_struct = items.iter_mut().find_map(|item| match item {
+        Item::Struct(item_struct) if TusksParameters::is_parameters_struct(item_struct) => Some(item_struct),
+        _ => None,
+    });
+    let parameters_struct = match parameters_struct {
+        Some(parameters_struct) => parameters_struct,
+        None => {
+            items.push(parse_quote! { pub struct Parameters<'a> {} });
+            let Some(Item::Struct(parameters_struct)) = items.last_mut() else {
+                unreachable!()
+            };
+            parameters_struct
+        }
+    };
+
+    let lifetime = TusksModule::complete_lifetime(parameters_struct);
+    let has_field = |item_struct: &ItemStruct, name: &str| {
+        item_struct.fields.iter().any(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+    };
+    let has_super = has_field(parameters_struct, "super_");
+    if let (Some(super_type), Fields::Named(fields), false) = (super_type, &mut parameters_struct.fields, has_super) {
+        let super_type = replace_lifetime(super_type, &lifetime);
+        fields.named.push(parse_quote! { pub super_: #super_type });
+    }
+    if !has_field(parameters_struct, "_phantom_lifetime_marker") {
+        // Tuple structs are an error the expansion reports already
+        let _ = TusksModule::add_phantom_field_to_struct(parameters_struct, &lifetime);
+    }
+
+    let vis = parameters_struct.vis.clone();
+    let name_is_taken = items.iter().any(|item| match item {
+        Item::Struct(item) => item.ident == "Parameters",
+        Item::Enum(item) => item.ident == "Parameters",
+        Item::Union(item) => item.ident == "Parameters",
+        Item::Type(item) => item.ident == "Parameters",
+        _ => false,
+    });
+    if !name_is_taken {
+        items.push(parse_quote! {
+            #[doc(hidden)]
+            #vis type Parameters<#lifetime> = #parameters_ident<#lifetime>;
+        });
+    }
+
+    for item in items.iter_mut() {
+        if let Item::Mod(item_mod) = item
+            && !is_skipped(&item_mod.attrs)
+        {
+            add_parameters_stand_ins(item_mod, Some(&parameters_ident));
+        }
+    }
+}
+
+/// Checks if a use tree imports the parent module as `parent_`
+fn declares_parent(tree: &UseTree) -> bool {
+    match tree {
+        UseTree::Path(use_path) => declares_parent(&use_path.tree),
+        UseTree::Name(use_name) => use_name.ident == "parent_",
+        UseTree::Rename(use_rename) => use_rename.rename == "parent_",
+        UseTree::Group(use_group) => use_group.items.iter().any(declares_parent),
+        UseTree::Glob(_) => false,
+    }
+}
+
+/// `&'a super::Parameters<'a>` with the lifetime of the struct instead of `'a`
+fn replace_lifetime(ty: Type, lifetime: &Lifetime) -> Type {
+    let tokens = quote! { #ty }.to_string().replace("'a", &lifetime.to_string());
+    syn::parse_str(&tokens).unwrap_or(ty)
+}
+
+/// Remove the tusks-only attributes from the module and the items tusks reads: functions,
+/// the parameters struct, `pub use` items and submodules. Items marked with `#[skip]` only lose
+/// that attribute (and `#[parameters]`), as their other attributes may belong to e.g. a
+/// `#[derive(clap::Args)]`.
+pub fn remove_tusks_attributes_from_module(module: &mut ItemMod) {
+    remove_tusks_attributes(&mut module.attrs);
+
+    let Some((_, ref mut items)) = module.content else {
+        return;
+    };
+
+    for item in items {
+        match item {
+            Item::Fn(item_fn) if !is_skipped(&item_fn.attrs) => {
+                remove_tusks_attributes(&mut item_fn.attrs);
+                for input in &mut item_fn.sig.inputs {
+                    match input {
+                        FnArg::Typed(pat_type) => remove_tusks_attributes(&mut pat_type.attrs),
+                        FnArg::Receiver(receiver) => remove_tusks_attributes(&mut receiver.attrs),
+                    }
+                }
+            }
+            Item::Struct(item_struct) if TusksParameters::is_parameters_struct(item_struct) => {
+                remove_tusks_attributes(&mut item_struct.attrs);
+                if let Fields::Named(fields) = &mut item_struct.fields {
+                    for field in &mut fields.named {
+                        remove_tusks_attributes(&mut field.attrs);
+                    }
+                }
+            }
+            Item::Use(item_use) => remove_tusks_attributes(&mut item_use.attrs),
+            Item::Mod(item_mod) if !is_skipped(&item_mod.attrs) => {
+                remove_tusks_attributes_from_module(item_mod);
+            }
+            // A skipped struct may still be marked with #[parameters]
+            Item::Fn(syn::ItemFn { attrs, .. })
+            | Item::Struct(syn::ItemStruct { attrs, .. })
+            | Item::Mod(ItemMod { attrs, .. }) => {
+                attrs.retain(|attr| !attr.path().is_ident("skip") && !attr.path().is_ident("parameters"));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_skipped(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("skip"))
+}
+
+fn remove_tusks_attributes(attrs: &mut Vec<Attribute>) {
+    attrs.retain_mut(|attr| {
+        let Some((predicate, wrapped)) = cfg_attr_arguments(attr) else {
+            return !is_tusks_attribute(attr.path());
+        };
+
+        // #[cfg_attr(predicate, ...)] keeps the attributes of other crates only
+        let wrapped: Vec<_> = wrapped.into_iter()
+            .filter(|meta| !is_tusks_attribute(meta.path()))
+            .collect();
+        if wrapped.is_empty() {
+            return false;
+        }
+        *attr = parse_quote! { #[cfg_attr(#predicate, #(#wrapped),*)] };
+        true
+    });
+}
+
+fn is_tusks_attribute(path: &syn::Path) -> bool {
+    TUSKS_ATTRIBUTES.iter().any(|name| path.is_ident(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_fallback_expansion() {
+        let module: ItemMod = parse_quote! {
+            #[command(about = "tasks")]
+            pub mod tasks {
+                #[default]
+                pub fn deploy(#[arg(long)] region: String) -> String { region }
+            }
+        };
+
+        let expansion = fallback_expansion(&module, syn::Error::new(Span::call_site(), "invalid"));
+        let expected = quote! {
+            pub mod tasks {
+                pub fn deploy(region: String) -> String { region }
+                pub struct Parameters<'a> {
+                    pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>
+                }
+            }
+            ::core::compile_error! { "invalid" }
+        };
+        assert_eq!(expansion.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_parameters_stand_ins() {
+        let mut module: ItemMod = parse_quote! {
+            pub mod tasks {
+                use super::cli as parent_;
+
+                #[parameters]
+                pub struct Options { #[arg(long)] pub verbose: bool }
+
+                pub mod deploy {
+                    pub fn run(params: &Parameters) -> bool { params.super_.verbose }
+                }
+            }
+        };
+
+        add_parameters_stand_ins(&mut module, None);
+        let expected = quote! {
+            pub mod tasks {
+                use super::cli as parent_;
+
+                #[parameters]
+                pub struct Options<'a> {
+                    #[arg(long)] pub verbose: bool,
+                    pub super_: &'a parent_::Parameters<'a>,
+                    pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>
+                }
+
+                pub mod deploy {
+                    pub fn run(params: &Parameters) -> bool { params.super_.verbose }
+                    pub struct Parameters<'a> {
+                        pub super_: &'a super::Options<'a>,
+                        pub _phantom_lifetime_marker: ::std::marker::PhantomData<&'a ()>
+                    }
+                }
+
+                #[doc(hidden)]
+                pub type Parameters<'a> = Options<'a>;
+            }
+        };
+        assert_eq!(quote! { #module }.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_remove_tusks_attributes_behind_cfg_attr() {
+        let mut module: ItemMod = parse_quote! {
+            pub mod tasks {
+                #[cfg_attr(feature = "cloud", command(name = "up"))]
+                #[cfg_attr(feature = "cloud", command(hide), must_use)]
+                #[cfg_attr(feature = "cloud", inline)]
+                pub fn upload(#[cfg_attr(unix, arg(long))] bucket: String) -> u8 { 0 }
+            }
+        };
+
+        remove_tusks_attributes_from_module(&mut module);
+        let expected = quote! {
+            pub mod tasks {
+                #[cfg_attr(feature = "cloud", must_use)]
+                #[cfg_attr(feature = "cloud", inline)]
+                pub fn upload(bucket: String) -> u8 { 0 }
+            }
+        };
+        assert_eq!(quote! { #module }.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_remove_parameters_marker_of_skipped_struct() {
+        let mut module: ItemMod = parse_quote! {
+            pub mod tasks {
+                #[skip]
+                #[parameters]
+                #[derive(Debug)]
+                pub struct Options { #[arg(long)] pub verbose: bool }
+            }
+        };
+
+        remove_tusks_attributes_from_module(&mut module);
+        let expected = quote! {
+            pub mod tasks {
+                #[derive(Debug)]
+                pub struct Options { #[arg(long)] pub verbose: bool }
+            }
+        };
+        assert_eq!(quote! { #module }.to_string(), expected.to_string());
+    }
+}
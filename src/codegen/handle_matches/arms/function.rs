@@ -2,6 +2,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 
 use crate::codegen::util::enum_util::convert_function_to_enum_variant;
+use crate::codegen::util::type_mapping::borrow_back;
 
 use crate::{TusksModule, models::Tusk};
 
@@ -42,7 +43,10 @@ impl TusksModule {
             false
         );
 
+        let cfg_attrs = tusk.cfg_attributes();
+
         quote! {
+            #(#cfg_attrs)*
             Some(#cli_path::Commands::#variant_ident { #(#pattern_fields),* }) => {
                 #function_call
             }
@@ -64,7 +68,10 @@ impl TusksModule {
             is_external_subcommand_case
         );
 
+        let cfg_attrs = tusk.cfg_attributes();
+
         quote! {
+            #(#cfg_attrs)*
             None => {
                 #function_call
             }
@@ -87,14 +94,17 @@ impl TusksModule {
             quote! { #ident:: }
         });
 
+        let cfg_attrs = tusk.cfg_attributes();
+
         quote! {
+            #(#cfg_attrs)*
             Some(cli::#(#path_tokens)*Commands::ClapExternalSubcommand(external_subcommand_args)) => {
                 #function_call
             }
         }
     }
 
-    /// Creates the function call with proper arguments and path, always returning Option<i32>
+    /// Creates the function call with proper arguments and path, always returning Option<u8>
     fn build_function_call(
         &self,
         tusk: &Tusk,
@@ -110,19 +120,29 @@ impl TusksModule {
             is_external_subcommand_case
         );
         let func_path = self.build_function_path(tusk, path);
+
+        // Async functions are driven by the executor set up in handle_matches
+        let call = if tusk.func.sig.asyncness.is_some() {
+            quote! { tusks_block_on!(#func_path(#(#func_args),*)) }
+        } else {
+            quote! { #func_path(#(#func_args),*) }
+        };
         
         match &tusk.func.sig.output {
             syn::ReturnType::Default => {
                 // Function returns () - call it and return None
-                quote! { #func_path(#(#func_args),*); None }
+                quote! { #call; None }
             }
             syn::ReturnType::Type(_, ty) => {
                 if Tusk::is_u8_type(ty) {
                     // Function returns u8 - call it and wrap in Some
-                    quote! { Some(#func_path(#(#func_args),*)) }
+                    quote! { Some(#call) }
                 } else if Tusk::is_option_u8_type(ty) {
                     // Function returns Option<u8> - call it and return as is
-                    quote! { #func_path(#(#func_args),*) }
+                    quote! { #call }
+                } else if let Some(ok_ty) = Tusk::result_ok_type(ty) {
+                    // Function returns Result<T, E> - report the error and map it to an exit code
+                    Self::build_result_handling(call, ok_ty)
                 } else {
                     // This should not happen due to validation
                     quote! { None }
@@ -131,6 +151,51 @@ impl TusksModule {
         }
     }
 
+    /// Handles the result of a function returning Result<T, E>.
+    /// The Ok value is converted like a plain return value of type T, the error is printed
+    /// to stderr (including its source chain) and mapped to a non-zero exit code.
+    ///
+    /// # Examples
+    ///
+    /// For a function `fn deploy() -> Result<u8, DeployError>`:
+    /// ```ignore
+    /// match super::deploy() {
+    ///     Ok(value) => Some(value),
+    ///     Err(error) => {
+    ///         let report = ::tusks::exit_code::Report(&error);
+    ///         eprintln!("Error: {}", (&&report).tusks_message());
+    ///         Some((&&report).tusks_exit_code())
+    ///     }
+    /// }
+    /// ```
+    fn build_result_handling(call: TokenStream, ok_ty: &syn::Type) -> TokenStream {
+        let ok_value = if Tusk::is_u8_type(ok_ty) {
+            quote! { Some(value) }
+        } else if Tusk::is_option_u8_type(ok_ty) {
+            quote! { value }
+        } else {
+            quote! { { let _: () = value; None } }
+        };
+
+        quote! {
+            match #call {
+                Ok(value) => #ok_value,
+                Err(error) => {
+                    #[allow(unused_imports)]
+                    use ::tusks::exit_code::{
+                        CustomExitCode as _,
+                        DefaultExitCode as _,
+                        DisplayMessage as _,
+                        ErrorChainMessage as _
+                    };
+                    let report = ::tusks::exit_code::Report(&error);
+                    eprintln!("Error: {}", (&&report).tusks_message());
+                    Some((&&report).tusks_exit_code())
+                }
+            }
+        }
+    }
+
     /// Creates bindings for function parameters (p1, p2, p3, ...).
     /// Skips the first parameter if it's &Parameters.
     /// 
@@ -189,12 +254,13 @@ impl TusksModule {
 
     /// Creates the arguments for the function call.
     /// Adds &parameters if present, followed by the bound parameters.
+    /// Owned arguments are cloned, borrowed ones are borrowed back from the clap fields.
     /// 
     /// # Examples
     /// 
-    /// For function with parameters:
+    /// For function `fn my_func(params: &Parameters, arg1: String, arg2: &str)`:
     /// ```rust
-    /// [&parameters, p1.clone(), p2.clone()]
+    /// [&parameters, (p1).clone(), (p2).as_str()]
     /// ```
     fn build_function_arguments(
         &self,
@@ -224,8 +290,12 @@ impl TusksModule {
             return func_args;
         }
 
-        for (_, binding_name) in pattern_bindings {
-            func_args.push(quote! { #binding_name.clone() });
+        for (field_name, binding_name) in pattern_bindings {
+            let arg_value = match tusk.argument_type(field_name) {
+                Some(ty) => borrow_back(ty, quote! { #binding_name }, &self.std_imports),
+                None => quote! { #binding_name.clone() },
+            };
+            func_args.push(arg_value);
         }
 
         func_args
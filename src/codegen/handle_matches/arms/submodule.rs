@@ -2,6 +2,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
 use crate::TusksModule;
+use crate::codegen::util::attribute::extract_cfg_attributes;
 use crate::codegen::util::enum_util::convert_submodule_to_enum_variant;
 
 impl TusksModule {
@@ -28,7 +29,7 @@ impl TusksModule {
     pub fn build_submodule_match_arm(&self, cli_path: &TokenStream, path: &[&str]) -> TokenStream {
         let variant_ident = self.build_variant_ident();
         let pattern_bindings = self.build_parameter_pattern_bindings();
-        let pattern_fields = self.build_pattern_fields(&pattern_bindings);
+        let pattern_fields = self.build_parameter_pattern_fields(&pattern_bindings);
         let has_commands = self.has_commands();
         let pattern_fields = self.add_sub_field_if_needed(pattern_fields, has_commands);
         let params_init = self.build_parameter_initialization(&pattern_bindings, path, has_commands);
@@ -79,15 +80,27 @@ impl TusksModule {
         bindings
     }
 
-    /// Checks if submodule has any commands (tusks, submodules, or external modules).
-    /// 
+    /// Creates the pattern fields for the parameter bindings.
+    /// Conditional fields keep their #[cfg] attributes.
+    ///
     /// # Example
-    /// Input: submodule with tusks or submodules
-    /// Output: true
-    fn has_commands(&self) -> bool {
-        !self.tusks.is_empty() || 
-        !self.submodules.is_empty() || 
-        !self.external_modules.is_empty()
+    /// Input: bindings = [("user", p1)], where user has `#[cfg(feature = "users")]`
+    /// Output: [quote! { #[cfg(feature = "users")] user: p1 }]
+    fn build_parameter_pattern_fields(
+        &self,
+        bindings: &[(syn::Ident, syn::Ident)]
+    ) -> Vec<TokenStream> {
+        bindings.iter()
+            .filter(|(field_name, _)| field_name != "_phantom_lifetime_marker")
+            .map(|(field_name, binding_name)| {
+                let cfg_attrs = self.parameters.iter()
+                    .flat_map(|params| params.pstruct.fields.iter())
+                    .find(|field| field.ident.as_ref() == Some(field_name))
+                    .map(|field| extract_cfg_attributes(&field.attrs))
+                    .unwrap_or_default();
+                quote! { #(#cfg_attrs)* #field_name: #binding_name }
+            })
+            .collect()
     }
 
     /// Adds the "sub" field to pattern if submodule has commands.
@@ -128,6 +141,10 @@ impl TusksModule {
         let params = self.parameters.as_ref().unwrap();
         let mut field_inits = Vec::new();
 
+        let submod_name_str = submod_name.to_string();
+        let mut module_path = path.to_vec();
+        module_path.push(&submod_name_str);
+
         for field in &params.pstruct.fields {
             if let Some(field_name) = &field.ident {
                 if field_name == "super_" {
@@ -141,20 +158,28 @@ impl TusksModule {
                     // Find the binding for this field
                     if let Some((_, binding_name)) = bindings.iter()
                         .find(|(fname, _)| fname == field_name) {
-                        field_inits.push(quote! { #field_name: #binding_name, });
+                        let value = params.build_field_value(
+                            field,
+                            quote! { #binding_name },
+                            &module_path,
+                            &self.std_imports
+                        );
+                        let cfg_attrs = extract_cfg_attributes(&field.attrs);
+                        field_inits.push(quote! { #(#cfg_attrs)* #field_name: #value, });
                     }
                 }
             }
         }
 
         // Build parameters path
+        let parameters_ident = &params.pstruct.ident;
         let params_path = if path.is_empty() {
-            quote! { super::#submod_name::Parameters }
+            quote! { super::#submod_name::#parameters_ident }
         } else {
             let path_idents: Vec<_> = path.iter()
                 .map(|p| syn::Ident::new(p, Span::call_site()))
                 .collect();
-            quote! { super::#(#path_idents)::*::#submod_name::Parameters }
+            quote! { super::#(#path_idents)::*::#submod_name::#parameters_ident }
         };
 
         quote! {
@@ -225,7 +250,10 @@ impl TusksModule {
         params_init: TokenStream,
         nested_match: TokenStream,
     ) -> TokenStream {
+        let cfg_attrs = self.cfg_attributes();
+
         quote! {
+            #(#cfg_attrs)*
             Some(#cli_path::Commands::#variant_ident { #(#pattern_fields),* }) => {
                 #params_init
                 #nested_match
@@ -0,0 +1,128 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{TusksModule, attribute::models::AsyncRuntime, models::Tusk};
+
+impl TusksModule {
+    /// Generates the `tusks_block_on!` macro used by the match arms of async commands.
+    /// The executor is created lazily on the first use, so at most once per invocation
+    /// and not at all if a synchronous command is executed.
+    ///
+    /// # Example
+    /// For `#[tusks(async_runtime = "tokio")]`:
+    /// ```ignore
+    /// let async_runtime = ::std::cell::OnceCell::new();
+    /// macro_rules! tusks_block_on {
+    ///     ($future:expr) => {
+    ///         async_runtime.get_or_init(|| /* build tokio runtime */).block_on($future)
+    ///     };
+    /// }
+    /// ```
+    pub fn build_async_runtime(&self, async_runtime: Option<&AsyncRuntime>) -> TokenStream {
+        let Some(first_async_tusk) = self.find_async_tusk() else {
+            return quote! {};
+        };
+
+        let Some(async_runtime) = async_runtime else {
+            return syn::Error::new_spanned(
+                first_async_tusk.func.sig.asyncness,
+                "async commands require an executor: \
+                    use #[tusks(async_runtime = \"tokio\" | \"futures\" | path)]"
+            ).to_compile_error();
+        };
+
+        let block_on = match async_runtime {
+            AsyncRuntime::Tokio => quote! {
+                async_runtime.get_or_init(|| {
+                    ::tusks::tokio::runtime::Builder::new_multi_thread()
+                        .enable_all()
+                        .build()
+                        .expect("failed to create the tokio runtime")
+                }).block_on($future)
+            },
+            AsyncRuntime::Futures => quote! {
+                ::tusks::futures::executor::block_on($future)
+            },
+            AsyncRuntime::Custom(constructor) => quote! {
+                async_runtime.get_or_init(#constructor).block_on($future)
+            },
+        };
+
+        let runtime_cell = match async_runtime {
+            AsyncRuntime::Futures => quote! {},
+            _ => quote! { let async_runtime = ::std::cell::OnceCell::new(); },
+        };
+
+        quote! {
+            #runtime_cell
+            #[allow(unused_macros)]
+            macro_rules! tusks_block_on {
+                ($future:expr) => { #block_on };
+            }
+        }
+    }
+
+    /// Find the first async command in this module or its submodules
+    fn find_async_tusk(&self) -> Option<&Tusk> {
+        self.tusks.iter()
+            .find(|tusk| tusk.func.sig.asyncness.is_some())
+            .or_else(|| self.submodules.iter().find_map(|submodule| submodule.find_async_tusk()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ItemMod, parse_quote};
+
+    fn handle_matches(module: ItemMod, async_runtime: Option<&AsyncRuntime>) -> String {
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        tusks_module.build_handle_matches(true, async_runtime).to_string()
+    }
+
+    fn async_module() -> ItemMod {
+        parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+                pub mod cloud {
+                    pub async fn upload() {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_async_arm_per_runtime() {
+        let tokio = handle_matches(async_module(), Some(&AsyncRuntime::Tokio));
+        assert!(tokio.contains("let async_runtime = :: std :: cell :: OnceCell :: new ()"));
+        assert!(tokio.contains(":: tusks :: tokio :: runtime :: Builder :: new_multi_thread ()"));
+        assert!(tokio.contains("tusks_block_on ! (super :: cloud :: upload ())"));
+
+        let futures = handle_matches(async_module(), Some(&AsyncRuntime::Futures));
+        assert!(!futures.contains("OnceCell"));
+        assert!(futures.contains(":: tusks :: futures :: executor :: block_on ($ future)"));
+        assert!(futures.contains("tusks_block_on ! (super :: cloud :: upload ())"));
+
+        let custom = AsyncRuntime::Custom(parse_quote! { crate::rt::build });
+        let custom = handle_matches(async_module(), Some(&custom));
+        assert!(custom.contains(
+            "async_runtime . get_or_init (crate :: rt :: build) . block_on ($ future)"
+        ));
+        assert!(custom.contains("tusks_block_on ! (super :: cloud :: upload ())"));
+    }
+
+    #[test]
+    fn test_async_runtime_required_only_for_async_commands() {
+        let missing = handle_matches(async_module(), None);
+        assert!(missing.contains("compile_error ! { \"async commands require an executor"));
+
+        let sync_module: ItemMod = parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+            }
+        };
+        let sync = handle_matches(sync_module, Some(&AsyncRuntime::Tokio));
+        assert!(!sync.contains("tusks_block_on"));
+        assert!(!sync.contains("tokio"));
+    }
+}
@@ -1,2 +1,3 @@
 mod module;
 mod arms;
+mod async_runtime;
@@ -2,13 +2,18 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
 use crate::AttributeCheck;
+use crate::codegen::util::attribute::extract_cfg_attributes;
 use crate::codegen::util::enum_util::convert_external_module_to_enum_variant;
 
-use crate::{TusksModule, models::Tusk};
+use crate::{TusksModule, attribute::models::AsyncRuntime, models::Tusk};
 
 impl TusksModule {
     /// Generate the handle_matches function
-    pub fn build_handle_matches(&self, is_tusks_root: bool) -> TokenStream {
+    pub fn build_handle_matches(
+        &self,
+        is_tusks_root: bool,
+        async_runtime: Option<&AsyncRuntime>
+    ) -> TokenStream {
         let signature = if is_tusks_root {
             quote! {
                 pub fn handle_matches(cli: &cli::Cli) -> Option<u8>
@@ -23,11 +28,13 @@ impl TusksModule {
         };
         
         let params_init = self.build_parameters_initialization();
+        let async_runtime = self.build_async_runtime(async_runtime);
         let match_arms = self.build_match_arms_recursive(&[]);
         
         quote! {
             #signature {
                 #params_init
+                #async_runtime
                 
                 let commands = &cli.sub;
                 match commands {
@@ -48,14 +55,24 @@ impl TusksModule {
                         "_phantom_lifetime_marker" => quote! {
                             _phantom_lifetime_marker: ::std::marker::PhantomData,
                         },
-                        _ => quote! { #field_name: &cli.#field_name, },
+                        _ => {
+                            let value = params.build_field_value(
+                                field,
+                                quote! { &cli.#field_name },
+                                &[],
+                                &self.std_imports
+                            );
+                            let cfg_attrs = extract_cfg_attributes(&field.attrs);
+                            quote! { #(#cfg_attrs)* #field_name: #value, }
+                        }
                     };
                     field_inits.push(field_init);
                 }
             }
             
+            let parameters_ident = &params.pstruct.ident;
             quote! {
-                let parameters = super::Parameters {
+                let parameters = super::#parameters_ident {
                     #(#field_inits)*
                 };
             }
@@ -113,6 +130,11 @@ impl TusksModule {
             arms.push(self.build_external_arm(&cli_path, path));
         }
 
+        // Conditional commands may be compiled out, leaving the enum incomplete or empty
+        if self.has_conditional_commands() {
+            arms.push(Self::build_unreachable_arm());
+        }
+
         arms
     }
 
@@ -156,13 +178,20 @@ impl TusksModule {
                 quote! { super::#(#path_idents)::*::#alias }
             };
 
+            let cfg_attrs = ext_mod.cfg_attributes();
+
             external_arms.push(quote! {
+                #(#cfg_attrs)*
                 #cli_path::ExternalCommands::#variant_ident(cli) => {
                     #external_path::__internal_tusks_module::handle_matches(cli, &parameters)
                 }
             });
         }
 
+        if self.has_conditional_external_modules() {
+            external_arms.push(Self::build_unreachable_arm());
+        }
+
         quote! {
             Some(#cli_path::Commands::TuskExternalCommands(commands)) => {
                 match commands {
@@ -171,6 +200,14 @@ impl TusksModule {
             }
         }
     }
+
+    /// Catch-all arm for matches over enums whose variants may be removed by #[cfg]
+    fn build_unreachable_arm() -> TokenStream {
+        quote! {
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("command is not available in this build"),
+        }
+    }
     
     pub fn tusk_has_parameters_arg(&self, tusk: &Tusk) -> bool {
         if let Some(syn::FnArg::Typed(first_param)) = tusk.func.sig.inputs.first() {
@@ -181,3 +218,44 @@ impl TusksModule {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ItemMod, parse_quote};
+
+    fn handle_matches(module: ItemMod) -> String {
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        tusks_module.build_handle_matches(true, None).to_string()
+    }
+
+    #[test]
+    fn test_unreachable_arm_for_conditional_commands() {
+        let unreachable_arm = TusksModule::build_unreachable_arm().to_string();
+        assert!(unreachable_arm.starts_with("# [allow (unreachable_patterns)] _ => unreachable !"));
+
+        let conditional = handle_matches(parse_quote! {
+            pub mod cli {
+                #[cfg(feature = "cloud")]
+                pub fn upload() {}
+            }
+        });
+        assert!(conditional.contains(&unreachable_arm));
+
+        let conditional_submodule = handle_matches(parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+                #[cfg(feature = "cloud")]
+                pub mod cloud { pub fn upload() {} }
+            }
+        });
+        assert!(conditional_submodule.contains(&unreachable_arm));
+
+        let unconditional = handle_matches(parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+            }
+        });
+        assert!(!unconditional.contains("unreachable_patterns"));
+    }
+}
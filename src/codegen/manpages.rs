@@ -0,0 +1,213 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::TusksModule;
+use crate::codegen::util::command_attribute::{command_name, is_hidden_command};
+
+impl TusksModule {
+    /// Generate `mod manpages` of a root module with `#[tusks(manpages)]`. Its `write_all`
+    /// writes one section-1 page per visible command and submodule, named like clap names the
+    /// pages of subcommands, e.g. `tool-deploy-start.1`.
+    ///
+    /// The pages follow the module tree: the Parameters of the parent modules, which a command
+    /// reaches through the `super_` chain, are listed as "GLOBAL OPTIONS". External modules are
+    /// walked at runtime, as only their own expansion knows their commands.
+    pub fn build_manpages(&self) -> TokenStream {
+        let mut pages = Vec::new();
+        self.collect_manpages(&[], &[], &mut pages);
+
+        quote! {
+            pub mod manpages {
+                use ::tusks::clap::{Arg, Command, CommandFactory as _};
+                use ::std::io;
+                use ::std::path::{Path, PathBuf};
+
+                /// Write the man pages to `dir`, which is created if missing.
+                /// Returns the written files.
+                pub fn write_all(dir: &Path) -> io::Result<Vec<PathBuf>> {
+                    // Building assigns the display names (`tool-sub`) the pages are named after
+                    let mut root = super::cli::Cli::command();
+                    root.build();
+
+                    let mut pages: Vec<(&[&str], bool)> = Vec::new();
+                    #(#pages)*
+
+                    ::std::fs::create_dir_all(dir)?;
+                    let mut written = Vec::new();
+                    for (path, recursive) in pages {
+                        write_page(&root, path, recursive, dir, &mut written)?;
+                    }
+                    Ok(written)
+                }
+
+                fn write_page(
+                    root: &Command,
+                    path: &[&str],
+                    recursive: bool,
+                    dir: &Path,
+                    written: &mut Vec<PathBuf>
+                ) -> io::Result<()> {
+                    let mut command = root;
+                    let mut global_options: Vec<&Arg> = Vec::new();
+                    for name in path {
+                        global_options.extend(command.get_arguments().filter(|arg| is_global_option(arg)));
+                        let Some(subcommand) = command.find_subcommand(name) else {
+                            return Err(io::Error::new(
+                                io::ErrorKind::NotFound,
+                                format!("`{}` has no subcommand `{}`", command.get_name(), name)
+                            ));
+                        };
+                        command = subcommand;
+                    }
+
+                    let mut page = command.clone();
+                    for arg in global_options {
+                        if page.get_arguments().all(|own| own.get_id() != arg.get_id()) {
+                            page = page.arg(arg.clone().help_heading("Global options"));
+                        }
+                    }
+
+                    // All pages carry the name and version of the tool, as a distro package does
+                    let source = format!("{} {}", root.get_name(), root.get_version().unwrap_or_default());
+                    let man = ::tusks::clap_mangen::Man::new(page).source(source);
+                    let mut buffer = Vec::new();
+                    man.render(&mut buffer)?;
+
+                    // The global options go in front of the command, not behind it as in the
+                    // synopsis of the page
+                    let mut synopsis = Vec::new();
+                    ::tusks::clap_mangen::Man::new(command.clone()).render_synopsis_section(&mut synopsis)?;
+                    let roff = ::tusks::man_roff::replace_synopsis(
+                        &String::from_utf8_lossy(&buffer),
+                        &String::from_utf8_lossy(&synopsis)
+                    );
+
+                    let file = dir.join(man.get_filename());
+                    ::std::fs::write(&file, roff)?;
+                    written.push(file);
+
+                    if recursive {
+                        for subcommand in command.get_subcommands() {
+                            if subcommand.is_hide_set() || subcommand.get_name() == "help" {
+                                continue;
+                            }
+                            let mut subpath = path.to_vec();
+                            subpath.push(subcommand.get_name());
+                            write_page(root, &subpath, true, dir, written)?;
+                        }
+                    }
+                    Ok(())
+                }
+
+                /// The Parameters fields of a module, `--help` and `--version` belong to every
+                /// command and global arguments are part of the subcommands already
+                fn is_global_option(arg: &Arg) -> bool {
+                    !arg.is_positional()
+                        && !arg.is_hide_set()
+                        && !arg.is_global_set()
+                        && !matches!(arg.get_id().as_str(), "help" | "version")
+                }
+            }
+        }
+    }
+
+    /// Statements pushing the command path of this module and all commands below it onto
+    /// `pages`, together with whether the command tree below is walked at runtime
+    fn collect_manpages(
+        &self,
+        path: &[String],
+        cfg_attrs: &[&syn::Attribute],
+        pages: &mut Vec<TokenStream>
+    ) {
+        let push = |name: Option<String>, extra_cfg: Vec<&syn::Attribute>, recursive: bool| {
+            let path = path.iter().cloned().chain(name);
+            quote! {
+                #(#cfg_attrs)*
+                #(#extra_cfg)*
+                pages.push((&[#(#path),*], #recursive));
+            }
+        };
+
+        pages.push(push(None, Vec::new(), false));
+
+        for tusk in &self.tusks {
+            let command_attrs = tusk.extract_attributes(&["command"]);
+            if is_hidden_command(&command_attrs) {
+                continue;
+            }
+            let name = command_name(&tusk.func.sig.ident, self.rename_all, &command_attrs);
+            pages.push(push(Some(name), tusk.cfg_attributes(), false));
+        }
+
+        for external_module in &self.external_modules {
+            let command_attrs = external_module.extract_attributes(&["command"]);
+            if is_hidden_command(&command_attrs) {
+                continue;
+            }
+            let name = command_name(&external_module.alias, self.rename_all, &command_attrs);
+            pages.push(push(Some(name), external_module.cfg_attributes(), true));
+        }
+
+        for submodule in &self.submodules {
+            let command_attrs = submodule.extract_attributes(&["command"]);
+            if is_hidden_command(&command_attrs) {
+                continue;
+            }
+
+            let mut subpath = path.to_vec();
+            subpath.push(command_name(&submodule.name, self.rename_all, &command_attrs));
+
+            let mut sub_cfg_attrs = cfg_attrs.to_vec();
+            sub_cfg_attrs.extend(submodule.cfg_attributes());
+
+            submodule.collect_manpages(&subpath, &sub_cfg_attrs, pages);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_collect_manpages() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+
+                #[command(hide = true)]
+                pub fn internal() {}
+
+                #[command(name = "cloud")]
+                pub use crate::cloud_ops;
+
+                pub mod deploy {
+                    pub fn start_all() {}
+
+                    #[cfg(feature = "k8s")]
+                    pub fn rollout() {}
+                }
+
+                #[command(hide)]
+                pub mod debug {
+                    pub fn dump() {}
+                }
+            }
+        };
+
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        let mut pages = Vec::new();
+        tusks_module.collect_manpages(&[], &[], &mut pages);
+
+        let pages: Vec<String> = pages.iter().map(|page| page.to_string()).collect();
+        assert_eq!(pages, [
+            "pages . push ((& [] , false)) ;",
+            "pages . push ((& [\"status\"] , false)) ;",
+            "pages . push ((& [\"cloud\"] , true)) ;",
+            "pages . push ((& [\"deploy\"] , false)) ;",
+            "pages . push ((& [\"deploy\" , \"start-all\"] , false)) ;",
+            "# [cfg (feature = \"k8s\")] pages . push ((& [\"deploy\" , \"rollout\"] , false)) ;",
+        ]);
+    }
+}
@@ -1,5 +1,9 @@
 mod cli;
 mod parameters;
 mod handle_matches;
-mod util;
+mod config;
+mod manpages;
+pub(crate) mod util;
 pub mod preparse;
+pub mod fallback;
+pub mod dump;
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Field, Ident, ItemStruct, Type};
+
+use crate::codegen::util::type_mapping::{borrow_back, owned_type};
+use crate::codegen::util::user_path::resolve_user_path;
+use crate::models::{FieldConversion, StdImports, TusksParameters};
+
+impl TusksParameters {
+    /// The type of the clap field generated for a Parameters field, `std_imports` are those of
+    /// the module declaring the struct
+    ///
+    /// # Examples
+    /// * `&'a Option<String>` → `Option<String>`
+    /// * `&'a str` → `String`
+    /// * `PathBuf` → `PathBuf`
+    /// * `#[tusks(from = load_config, arg_type = PathBuf)] Config` → `PathBuf`
+    pub fn cli_field_type(&self, field: &Field, std_imports: &StdImports) -> Type {
+        if let Some(conversion) = field.ident.as_ref().and_then(|name| self.find_conversion(name)) {
+            return conversion.arg_type.clone();
+        }
+
+        owned_type(&field.ty, std_imports)
+    }
+
+    /// Builds the value of a Parameters field from a reference to the parsed clap argument.
+    /// Conversion functions are resolved relative to the module at `module_path`,
+    /// where the Parameters struct is declared, which has the imports `std_imports`.
+    ///
+    /// # Examples
+    /// For `arg_ref` = `&cli.name`:
+    /// * `name: &'a String` → `&cli.name`
+    /// * `name: &'a str` → `(&cli.name).as_str()`
+    /// * `name: String` → `(&cli.name).clone()`
+    /// * `#[tusks(from = load_config)] name: Config` → `super::load_config(&cli.name)`
+    /// * `#[tusks(try_from = load_config)] name: Config` → the value of
+    ///   `super::load_config(&cli.name)`, an error exits with a clap error
+    pub fn build_field_value(
+        &self,
+        field: &Field,
+        arg_ref: TokenStream,
+        module_path: &[&str],
+        std_imports: &StdImports
+    ) -> TokenStream {
+        if let Some(conversion) = field.ident.as_ref().and_then(|name| self.find_conversion(name)) {
+            let from = resolve_user_path(&conversion.from, module_path);
+            if !conversion.fallible {
+                return quote! { #from(#arg_ref) };
+            }
+
+            let field_name = conversion.field.to_string();
+            return quote! {
+                match #from(#arg_ref) {
+                    Ok(value) => value,
+                    Err(error) => ::tusks::clap::Error::raw(
+                        ::tusks::clap::error::ErrorKind::ValueValidation,
+                        format!("invalid value for `{}`: {}\n", #field_name, error)
+                    ).exit(),
+                }
+            };
+        }
+
+        borrow_back(&field.ty, arg_ref, std_imports)
+    }
+
+    fn find_conversion(&self, field_name: &Ident) -> Option<&FieldConversion> {
+        self.conversions.iter().find(|conversion| conversion.field == *field_name)
+    }
+
+    /// Remove the #[tusks(...)] field attributes, which are only meaningful to tusks
+    pub fn remove_tusks_field_attributes(item_struct: &mut ItemStruct) {
+        for field in item_struct.fields.iter_mut() {
+            field.attrs.retain(|attr| !attr.path().is_ident("tusks"));
+        }
+    }
+}
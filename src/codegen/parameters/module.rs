@@ -1,8 +1,10 @@
-use syn::{Field, Fields, GenericParam, ItemMod, ItemStruct, Lifetime, Type};
+use syn::{Field, Fields, GenericParam, Ident, ItemMod, ItemStruct, Lifetime, Type, Visibility, parse_quote};
 use quote::quote;
 use proc_macro2::Span;
 
 use crate::{TusksModule, models::TusksParameters};
+use crate::codegen::util::type_mapping::add_elided_lifetimes;
+use crate::parsing::util::diagnostics::Diagnostics;
 
 impl TusksModule {
     /// Supplement Parameters structs where missing and add super_ fields
@@ -11,22 +13,48 @@ impl TusksModule {
         module: &mut ItemMod, 
         is_tusks_root: bool,
         derive_debug: bool,
+    ) -> syn::Result<()> {
+        self.supplement_module_parameters(module, is_tusks_root, derive_debug, None)
+    }
+
+    /// The name of the parameters struct: `Parameters`, or the name of the struct marked with
+    /// `#[parameters]`
+    pub fn parameters_ident(&self) -> Ident {
+        match self.parameters {
+            Some(ref params) => params.pstruct.ident.clone(),
+            None => Ident::new("Parameters", Span::call_site()),
+        }
+    }
+
+    /// `parent_parameters` is the name of the parameters struct of the parent module,
+    /// `None` for the tusks module itself
+    fn supplement_module_parameters(
+        &mut self,
+        module: &mut ItemMod,
+        is_tusks_root: bool,
+        derive_debug: bool,
+        parent_parameters: Option<&Ident>,
     ) -> syn::Result<()> {
         // 1. Get or create Parameters struct with lifetime
-        let lifetime = if let Some(ref params) = self.parameters {
-            // Extract lifetime from existing struct
-            Self::extract_lifetime(&params.pstruct)?
+        let lifetime = if self.parameters.is_some() {
+            // Extract lifetime from existing struct, adding it if it is missing
+            let parameters_struct = Self::find_parameters_struct_mut(module, &self.parameters_ident())?;
+            Self::complete_lifetime(parameters_struct)
         } else {
             self.add_parameters_struct(module, derive_debug)?
         };
 
-        let mut parameters_struct = Self::find_parameters_struct_mut(module)?;
+        let parameters_ident = self.parameters_ident();
+        let mut parameters_struct = Self::find_parameters_struct_mut(module, &parameters_ident)?;
+        TusksParameters::remove_tusks_field_attributes(parameters_struct);
         
         // 2. Add super_ field if needed
         if !is_tusks_root {
             self.add_super_field_to_parameters_struct(
                 &mut parameters_struct,
-                &lifetime)?;
+                &lifetime,
+                parent_parameters
+            )?;
         }
 
         Self::add_phantom_field_to_struct(&mut parameters_struct, &lifetime)?;
@@ -35,8 +63,15 @@ impl TusksModule {
         if let Some(ref mut params) = self.parameters {
             params.pstruct = parameters_struct.clone();
         }
+
+        // Tusks modules declaring this module as their parent_ refer to parent_::Parameters
+        if parameters_ident != "Parameters" {
+            let vis = parameters_struct.vis.clone();
+            Self::add_struct_alias_to_module(module, &vis, &parameters_ident, &lifetime)?;
+        }
         
-        // 3. Recursively process submodules
+        // 3. Recursively process submodules, reporting the errors of all of them
+        let mut diagnostics = Diagnostics::new();
         if let Some((_, ref mut items)) = module.content {
             for submodule_data in &mut self.submodules {
                 // Find corresponding ItemMod in module items
@@ -48,17 +83,21 @@ impl TusksModule {
                     }
                     None
                 }) {
+                    // #[tusks(...)] of submodules is resolved already, it must not expand again
+                    item_mod.attrs.retain(|attr| !Self::is_module_config_attribute(attr));
+
                     // Recursively supplement (submodules are never tusks root)
-                    submodule_data.supplement_parameters(
+                    diagnostics.check(submodule_data.supplement_module_parameters(
                         item_mod,
                         false,
-                        derive_debug
-                    )?;
+                        derive_debug,
+                        Some(&parameters_ident)
+                    ));
                 }
             }
         }
         
-        Ok(())
+        diagnostics.finish()
     }
     
     /// Extract the first lifetime parameter from a struct
@@ -76,6 +115,26 @@ impl TusksModule {
         ))
     }
     
+    /// Get the lifetime of a user-written Parameters struct. A struct without lifetime gets `'a`,
+    /// and the references in its fields without lifetime get the one of the struct.
+    ///
+    /// # Example
+    /// `pub struct Parameters { pub name: &String }`
+    /// becomes `pub struct Parameters<'a> { pub name: &'a String }`
+    pub(crate) fn complete_lifetime(item_struct: &mut ItemStruct) -> Lifetime {
+        let lifetime = Self::extract_lifetime(item_struct).unwrap_or_else(|_| {
+            let lifetime = Lifetime::new("'a", Span::call_site());
+            item_struct.generics.params.insert(0, parse_quote! { #lifetime });
+            lifetime
+        });
+
+        for field in &mut item_struct.fields {
+            add_elided_lifetimes(&mut field.ty, &lifetime);
+        }
+
+        lifetime
+    }
+
     /// Create a new empty Parameters struct with the given lifetime
     fn add_parameters_struct(
         &mut self,
@@ -102,7 +161,7 @@ impl TusksModule {
 
         Self::add_struct_to_module(module, params_struct.clone())?;
 
-        self.parameters = Some(TusksParameters{ pstruct: params_struct });
+        self.parameters = Some(TusksParameters{ pstruct: params_struct, conversions: Vec::new() });
 
         Ok(lifetime)
     }
@@ -120,11 +179,14 @@ impl TusksModule {
         }
     }
     
-    fn find_parameters_struct_mut(module: &mut ItemMod) -> syn::Result<&mut ItemStruct> {
+    fn find_parameters_struct_mut<'m>(
+        module: &'m mut ItemMod,
+        parameters_ident: &Ident
+    ) -> syn::Result<&'m mut ItemStruct> {
         if let Some((_, ref mut items)) = module.content {
             for item in items.iter_mut() {
                 if let syn::Item::Struct(s) = item {
-                    if s.ident == "Parameters" {
+                    if s.ident == *parameters_ident {
                         return Ok(s);
                     }
                 }
@@ -133,23 +195,66 @@ impl TusksModule {
 
         Err(syn::Error::new_spanned(
             &module.ident,
-            "Parameters struct not found in module"
+            format!("{} struct not found in module", parameters_ident)
         ))
     }
 
+    /// Add `pub type Parameters<'a> = DeployOpts<'a>;` for a parameters struct with another name
+    ///
+    /// # Errors
+    /// Fails if the module declares another type named `Parameters` (e.g. a struct with
+    /// `#[skip]`), as external modules declaring this module as their `parent_` refer to
+    /// `parent_::Parameters`.
+    fn add_struct_alias_to_module(
+        module: &mut ItemMod,
+        vis: &Visibility,
+        parameters_ident: &Ident,
+        lifetime: &Lifetime
+    ) -> syn::Result<()> {
+        let Some((_, ref mut items)) = module.content else {
+            return Ok(());
+        };
+
+        let taken_by = items.iter().find_map(|item| match item {
+            syn::Item::Struct(item) if item.ident == "Parameters" => Some(&item.ident),
+            syn::Item::Enum(item) if item.ident == "Parameters" => Some(&item.ident),
+            syn::Item::Union(item) if item.ident == "Parameters" => Some(&item.ident),
+            syn::Item::Type(item) if item.ident == "Parameters" => Some(&item.ident),
+            _ => None,
+        });
+        if let Some(ident) = taken_by {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!(
+                    "the name `Parameters` is needed for the alias of the parameters struct `{}`, \
+                        which external modules reach as `parent_::Parameters`: rename this item",
+                    parameters_ident
+                )
+            ));
+        }
+
+        items.push(parse_quote! {
+            #[doc(hidden)]
+            #vis type Parameters<#lifetime> = #parameters_ident<#lifetime>;
+        });
+        Ok(())
+    }
+
     /// Add super_ field to the Parameters struct in the module
     fn add_super_field_to_parameters_struct(
         &mut self,
         parameters_struct: &mut ItemStruct,
-        lifetime: &Lifetime
+        lifetime: &Lifetime,
+        parent_parameters: Option<&Ident>
     ) -> syn::Result<()> {
         // Determine the type of super_ based on whether we have an external parent
-        let super_type = if self.external_parent.is_some() {
-            // Local root with external parent: &'lifetime parent_::Parameters<'lifetime>
-            quote! { &#lifetime parent_::Parameters<#lifetime> }
-        } else {
+        let super_type = match parent_parameters {
             // Submodule: &'lifetime super::Parameters<'lifetime>
-            quote! { &#lifetime super::Parameters<#lifetime> }
+            Some(parent_parameters) if self.external_parent.is_none() => {
+                quote! { &#lifetime super::#parent_parameters<#lifetime> }
+            }
+            // Local root with external parent: &'lifetime parent_::Parameters<'lifetime>
+            _ => quote! { &#lifetime parent_::Parameters<#lifetime> },
         };
 
         // Parse the type
@@ -180,7 +285,7 @@ impl TusksModule {
         Ok(())
     }
 
-    fn add_phantom_field_to_struct(
+    pub(crate) fn add_phantom_field_to_struct(
         item_struct: &mut ItemStruct,
         lifetime: &Lifetime
     ) -> syn::Result<()> {
@@ -227,3 +332,74 @@ impl TusksModule {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_aliases(module: &ItemMod) -> Vec<String> {
+        module.content.as_ref().unwrap().1.iter()
+            .filter_map(|item| match item {
+                syn::Item::Type(item_type) => Some(quote! { #item_type }.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_add_struct_alias_to_module() {
+        let vis: Visibility = parse_quote! { pub };
+        let ident: Ident = parse_quote! { DeployOpts };
+        let lifetime: Lifetime = parse_quote! { 'a };
+
+        let mut module: ItemMod = parse_quote! {
+            pub mod deploy {
+                #[parameters]
+                pub struct DeployOpts<'a> { pub region: &'a String }
+            }
+        };
+        TusksModule::add_struct_alias_to_module(&mut module, &vis, &ident, &lifetime).unwrap();
+        assert_eq!(type_aliases(&module), [
+            "# [doc (hidden)] pub type Parameters < 'a > = DeployOpts < 'a > ;"
+        ]);
+
+        let mut module: ItemMod = parse_quote! {
+            pub mod deploy {
+                #[parameters]
+                pub struct DeployOpts<'a> { pub region: &'a String }
+                #[skip]
+                pub struct Parameters { pub other: u8 }
+            }
+        };
+        let error = TusksModule::add_struct_alias_to_module(&mut module, &vis, &ident, &lifetime)
+            .unwrap_err();
+        assert!(error.to_string().starts_with(
+            "the name `Parameters` is needed for the alias of the parameters struct `DeployOpts`"
+        ));
+        assert!(type_aliases(&module).is_empty());
+    }
+
+    #[test]
+    fn test_marked_parameters_struct() {
+        let mut module: ItemMod = parse_quote! {
+            pub mod cli {
+                #[parameters]
+                pub(crate) struct CliOpts<'a> {
+                    #[arg(long)]
+                    pub verbose: &'a bool,
+                }
+                pub fn status(opts: &CliOpts) {}
+            }
+        };
+
+        let mut tusks_module = TusksModule::from_module(module.clone(), true, 0, false, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tusks_module.parameters_ident(), "CliOpts");
+
+        tusks_module.supplement_parameters(&mut module, true, false).unwrap();
+        assert_eq!(type_aliases(&module), [
+            "# [doc (hidden)] pub (crate) type Parameters < 'a > = CliOpts < 'a > ;"
+        ]);
+    }
+}
@@ -0,0 +1,81 @@
+use quote::quote;
+use syn::{Item, ItemFn, ItemMod, parse_quote};
+
+/// Adds the `completions <shell>` command of `#[tusks(completions)]`, which prints the
+/// completion script of the whole command tree for bash, zsh, fish, elvish or powershell.
+///
+/// The script is generated by `clap_complete`, which the runtime crate re-exports with the
+/// `completions` feature. In tasks mode (`with_tasks`) the completion of the dotted task
+/// names is appended, see `add_complete_task_function`.
+///
+/// # Example
+/// ```ignore
+/// mytool completions bash > ~/.local/share/bash-completion/completions/mytool
+/// ```
+pub fn add_completions_function(module: &mut ItemMod, with_tasks: bool) {
+    let task_completion = if with_tasks {
+        quote! {
+            if let Some(task_script) = _task_completion_script(&shell.to_string(), &name) {
+                script.extend_from_slice(task_script.as_bytes());
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let function: ItemFn = parse_quote! {
+        #[command(about = "Print the shell completion script", name = "completions")]
+        pub fn _completions(#[arg(value_enum)] shell: ::tusks::clap_complete::Shell) -> Option<u8> {
+            use ::std::io::Write as _;
+
+            let mut command = <__internal_tusks_module::cli::Cli as ::tusks::clap::CommandFactory>::command();
+            let name = command.get_name().to_string();
+
+            // clap_complete panics if it cannot write, e.g. when piped into `head`
+            let mut script = Vec::new();
+            ::tusks::clap_complete::generate(shell, &mut command, name.clone(), &mut script);
+            #task_completion
+            match ::std::io::stdout().write_all(&script) {
+                Ok(()) => Some(0),
+                Err(_) => Some(1),
+            }
+        }
+    };
+
+    if let Some((_, ref mut items)) = module.content {
+        items.push(Item::Fn(function));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TusksModule;
+    use crate::codegen::util::command_attribute::command_name;
+
+    /// The body of the `completions` command, which must be picked up as a command
+    fn completions_body(with_tasks: bool) -> String {
+        let mut module: ItemMod = parse_quote! {
+            pub mod cli {
+                pub fn build() {}
+            }
+        };
+        add_completions_function(&mut module, with_tasks);
+
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        let completions = tusks_module.tusks.iter()
+            .find(|tusk| tusk.func.sig.ident == "_completions")
+            .expect("`_completions` is a command");
+        let command_attrs = completions.extract_attributes(&["command"]);
+        assert_eq!(command_name(&completions.func.sig.ident, None, &command_attrs), "completions");
+
+        let block = &completions.func.block;
+        quote! { #block }.to_string()
+    }
+
+    #[test]
+    fn test_completions_function() {
+        assert!(completions_body(true).contains("_task_completion_script"));
+        assert!(!completions_body(false).contains("_task_completion_script"));
+    }
+}
@@ -0,0 +1,498 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Group, LineColumn, Span, TokenStream, TokenTree};
+use syn::{AttrStyle, ItemMod, parse_quote, spanned::Spanned};
+
+use crate::AttributeCheck;
+use crate::AttributeValue;
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::parsing::util::visibility::is_eligible_visibility;
+
+/// The module files inlined into a tusks module, to tell which file an error points into
+#[derive(Default)]
+pub struct InlinedFiles {
+    files: Vec<InlinedFile>,
+}
+
+struct InlinedFile {
+    path: PathBuf,
+    /// The span of a token of the file if its tokens keep their positions, otherwise the span
+    /// of the `mod` declaration all its tokens were given
+    anchor: Span,
+    /// The range of the `mod` declaration, which the tokens of the file carry if not located
+    declaration: SpanRange,
+    located: bool,
+}
+
+/// The source range of a span. Inside the compiler the byte range is only known on nightly and
+/// the lines and columns from Rust 1.88 on, together they tell declarations apart.
+#[derive(PartialEq)]
+struct SpanRange {
+    bytes: Range<usize>,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+impl SpanRange {
+    fn of(span: Span) -> Self {
+        SpanRange { bytes: span.byte_range(), start: span.start(), end: span.end() }
+    }
+}
+
+impl InlinedFiles {
+    /// The files that were read, also those which could not be parsed
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|file| file.path.as_path())
+    }
+
+    /// The file `span` points into, as `file:line:column` if the position is known.
+    ///
+    /// Inside the compiler the tokens of nested module files all carry the same declaration,
+    /// then every candidate file is named.
+    pub fn position(&self, span: Span) -> Option<String> {
+        if let Some(file) = self.located_file(span) {
+            return Some(format_position(file, Some(span.start())));
+        }
+
+        let range = SpanRange::of(span);
+        let candidates: Vec<String> = self.files.iter()
+            .filter(|file| !file.located && file.declaration == range)
+            .map(|file| format!("`{}`", file.path.display()))
+            .collect();
+
+        (!candidates.is_empty()).then(|| candidates.join(" or "))
+    }
+
+    /// The file `span` points into, if its tokens keep their positions
+    pub(crate) fn located_file(&self, span: Span) -> Option<&Path> {
+        self.files.iter()
+            .find(|file| file.located && span.join(file.anchor).is_some())
+            .map(|file| file.path.as_path())
+    }
+
+    /// Adds the position in the module file to the message of every error inside one
+    pub fn locate(&self, error: syn::Error) -> syn::Error {
+        let mut diagnostics = Diagnostics::new();
+        for error in error {
+            match self.position(error.span()) {
+                Some(position) => diagnostics.push(
+                    syn::Error::new(error.span(), format!("{} (in {})", error, position))
+                ),
+                None => diagnostics.push(error),
+            }
+        }
+        diagnostics.finish().expect_err("a syn::Error holds at least one error")
+    }
+}
+
+/// Replaces every file module (`pub mod foo;`) inside the tusks module by an inline module
+/// containing the items of its source file, so that the rest of the pipeline only ever sees
+/// inline modules.
+///
+/// Files are looked up with the standard layout: `pub mod foo;` resolves to `foo.rs` or
+/// `foo/mod.rs` in the directory owned by the declaring module, and `#[path = "..."]` is
+/// honored. The source file of the tusks module is taken from the span of its name. If the
+/// compiler does not provide it, `$CARGO_MANIFEST_DIR/src` is assumed to be the directory of the
+/// crate root.
+///
+/// Inside the compiler, the tokens of a file read by a proc-macro all point to the `#[tusks]`
+/// attribute. The tokens of an inlined file get the span of its `pub mod foo;` declaration
+/// instead, and `InlinedFiles::locate` names the file in the errors of tusks. Build scripts and
+/// tests keep the positions in the file, so their errors carry `file:line:column`.
+/// An `include_str!` of the file is added to the inlined module to rebuild when the file changes.
+///
+/// # Example
+/// ```ignore
+/// #[tusks(root)]
+/// pub mod cli {
+///     pub mod deploy; // src/cli/deploy.rs or src/cli/deploy/mod.rs
+/// }
+/// ```
+pub fn inline_file_modules(module: &mut ItemMod) -> syn::Result<InlinedFiles> {
+    let source_file = root_source_file(module.ident.span());
+    let mut files = InlinedFiles::default();
+    inline_file_modules_in(module, &source_file, &mut files)?;
+    Ok(files)
+}
+
+/// Like `inline_file_modules` for a module written in `source_file`, for callers which know the
+/// file, like build scripts.
+///
+/// The files read are added to `files`, also if an error occurs.
+pub fn inline_file_modules_in(
+    module: &mut ItemMod,
+    source_file: &Path,
+    files: &mut InlinedFiles
+) -> syn::Result<()> {
+    let dir = owned_directory(source_file).join(module.ident.to_string());
+    inline_submodules(module, &dir, &dir, source_file, files)
+}
+
+/// Inline all file modules among the items of `module` and recurse into all submodules
+///
+/// # Arguments
+/// * `dir` - The directory owned by `module`, where its file modules are looked up
+/// * `path_base` - The directory that `#[path = "..."]` attributes are relative to
+/// * `source_file` - The file `module` is written in (used for error messages)
+/// * `files` - Collects the files that were read
+fn inline_submodules(
+    module: &mut ItemMod,
+    dir: &Path,
+    path_base: &Path,
+    source_file: &Path,
+    files: &mut InlinedFiles
+) -> syn::Result<()> {
+    let Some((_, ref mut items)) = module.content else {
+        return Ok(());
+    };
+
+    for item in items.iter_mut() {
+        let syn::Item::Mod(submodule) = item else {
+            continue;
+        };
+
+        // Only modules that can become tusks submodules need to be resolved
+        if !is_eligible_visibility(&submodule.vis) || submodule.has_attr("skip") {
+            continue;
+        }
+
+        if submodule.content.is_some() {
+            let sub_dir = dir.join(submodule.ident.to_string());
+            inline_submodules(submodule, &sub_dir, &sub_dir, source_file, files)?;
+            continue;
+        }
+
+        let (file, sub_dir) = resolve_module_file(submodule, dir, path_base, source_file)?;
+        load_module_file(submodule, &file, files)?;
+
+        let file_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        inline_submodules(submodule, &sub_dir, &file_dir, &file, files)?;
+    }
+
+    Ok(())
+}
+
+/// Find the file of a file module and the directory owned by it
+///
+/// # Errors
+/// Returns an error if no file or more than one candidate file exists
+fn resolve_module_file(
+    submodule: &ItemMod,
+    dir: &Path,
+    path_base: &Path,
+    source_file: &Path
+) -> syn::Result<(PathBuf, PathBuf)> {
+    let name = submodule.ident.to_string();
+
+    // Files loaded via #[path] own their parent directory, like mod.rs files
+    if let Some(path) = submodule.get_attribute_value("path", "path") {
+        let file = path_base.join(path);
+        if !file.is_file() {
+            return Err(syn::Error::new_spanned(
+                &submodule.ident,
+                format!(
+                    "file not found for module `{}` (declared in `{}`): `{}` does not exist",
+                    name,
+                    source_file.display(),
+                    file.display()
+                )
+            ));
+        }
+        let sub_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        return Ok((file, sub_dir));
+    }
+
+    let sub_dir = dir.join(&name);
+    let flat_file = dir.join(format!("{}.rs", name));
+    let mod_file = sub_dir.join("mod.rs");
+
+    match (flat_file.is_file(), mod_file.is_file()) {
+        (true, false) => Ok((flat_file, sub_dir)),
+        (false, true) => Ok((mod_file, sub_dir)),
+        (true, true) => Err(syn::Error::new_spanned(
+            &submodule.ident,
+            format!(
+                "file for module `{}` found at both `{}` and `{}`",
+                name,
+                flat_file.display(),
+                mod_file.display()
+            )
+        )),
+        (false, false) => Err(syn::Error::new_spanned(
+            &submodule.ident,
+            format!(
+                "file not found for module `{}` (declared in `{}`): \
+                    expected `{}` or `{}`",
+                name,
+                source_file.display(),
+                flat_file.display(),
+                mod_file.display()
+            )
+        )),
+    }
+}
+
+/// Read and parse a module file and turn `submodule` into an inline module with its items
+fn load_module_file(
+    submodule: &mut ItemMod,
+    file: &Path,
+    files: &mut InlinedFiles
+) -> syn::Result<()> {
+    let declaration_span = submodule.span();
+
+    let source = std::fs::read_to_string(file).map_err(|e| {
+        syn::Error::new_spanned(
+            &submodule.ident,
+            format!("failed to read module file `{}`: {}", file.display(), e)
+        )
+    })?;
+
+    files.files.push(InlinedFile {
+        path: file.to_path_buf(),
+        anchor: declaration_span,
+        declaration: SpanRange::of(declaration_span),
+        located: false,
+    });
+
+    let tokens: TokenStream = source.parse().map_err(|e: proc_macro2::LexError| {
+        let start = has_source_location(e.span()).then(|| e.span().start());
+        syn::Error::new_spanned(
+            &submodule.ident,
+            format!("failed to tokenize module file {}: {}", format_position(file, start), e)
+        )
+    })?;
+
+    let tokens = match tokens.clone().into_iter().next() {
+        Some(first) if has_source_location(first.span()) => {
+            if let Some(inlined) = files.files.last_mut() {
+                inlined.anchor = first.span();
+                inlined.located = true;
+            }
+            tokens
+        }
+        _ => respan(tokens, declaration_span),
+    };
+
+    let parsed: syn::File = syn::parse2(tokens).map_err(|e| {
+        let position = files.position(e.span())
+            .filter(|_| files.files.last().is_some_and(|inlined| inlined.located))
+            .unwrap_or_else(|| format_position(file, None));
+        syn::Error::new_spanned(
+            &submodule.ident,
+            format!("failed to parse module file {}: {}", position, e)
+        )
+    })?;
+
+    // Inner attributes of the file (e.g. `//!` docs) become attributes of the module
+    for mut attr in parsed.attrs {
+        attr.style = AttrStyle::Outer;
+        submodule.attrs.push(attr);
+    }
+
+    // The path is resolved already, an inline module must not keep it
+    submodule.attrs.retain(|attr| !attr.path().is_ident("path"));
+
+    let file_str = file.to_string_lossy();
+    let mut items: Vec<syn::Item> = vec![parse_quote! {
+        const _: &str = include_str!(#file_str);
+    }];
+    items.extend(parsed.items);
+
+    submodule.content = Some((syn::token::Brace(declaration_span), items));
+    submodule.semi = None;
+
+    Ok(())
+}
+
+/// Determine the file the tusks module is written in
+fn root_source_file(span: Span) -> PathBuf {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let Some(file) = span.local_file() else {
+        return manifest_dir.join("src").join("main.rs");
+    };
+
+    if file.is_absolute() {
+        return file;
+    }
+
+    // Relative paths are relative to the working directory of the compiler. The result must be
+    // absolute, as it is used in `include_str!`.
+    match std::env::current_dir() {
+        Ok(cwd) if cwd.join(&file).is_file() => cwd.join(file),
+        _ => manifest_dir.join(file),
+    }
+}
+
+/// The directory that holds the files of the modules declared at the top level of `file`
+///
+/// # Examples
+/// * `src/main.rs` → `src`
+/// * `src/cli/mod.rs` → `src/cli`
+/// * `src/cli.rs` → `src/cli`
+fn owned_directory(file: &Path) -> PathBuf {
+    let parent = file.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    if is_mod_rs_file(file) {
+        parent
+    } else {
+        parent.join(file.file_stem().unwrap_or_default())
+    }
+}
+
+/// Check whether a file owns its parent directory (`mod.rs` and crate roots)
+///
+/// Crate roots are detected by their conventional locations, as the compiler does not tell
+/// which file the crate root is.
+fn is_mod_rs_file(file: &Path) -> bool {
+    let file_name = file.file_name().and_then(|name| name.to_str());
+    if matches!(file_name, Some("mod.rs" | "lib.rs" | "main.rs")) {
+        return true;
+    }
+
+    let parent_name = file.parent()
+        .and_then(|parent| parent.file_name())
+        .and_then(|name| name.to_str());
+    matches!(parent_name, Some("bin" | "examples" | "tests" | "benches"))
+}
+
+/// Whether `span` has a position of its own. Inside the compiler, tokens parsed from a string
+/// all carry the call site.
+fn has_source_location(span: Span) -> bool {
+    span.end() != Span::call_site().end()
+}
+
+/// `file:line:column`, or only the file if the position is unknown
+fn format_position(file: &Path, start: Option<LineColumn>) -> String {
+    match start {
+        Some(start) => format!("`{}:{}:{}`", file.display(), start.line, start.column + 1),
+        None => format!("`{}`", file.display()),
+    }
+}
+
+/// Give every token in `tokens` the span `span`
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens.into_iter().map(|token| match token {
+        TokenTree::Group(group) => {
+            let mut new_group = Group::new(group.delimiter(), respan(group.stream(), span));
+            new_group.set_span(span);
+            TokenTree::Group(new_group)
+        }
+        mut other => {
+            other.set_span(span);
+            other
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owned_directory() {
+        assert_eq!(owned_directory(Path::new("src/main.rs")), PathBuf::from("src"));
+        assert_eq!(owned_directory(Path::new("src/cli/mod.rs")), PathBuf::from("src/cli"));
+        assert_eq!(owned_directory(Path::new("src/cli.rs")), PathBuf::from("src/cli"));
+        assert_eq!(owned_directory(Path::new("src/bin/tool.rs")), PathBuf::from("src/bin"));
+    }
+
+    #[test]
+    fn test_inline_file_modules() {
+        let dir = std::env::temp_dir().join(format!("tusks_file_modules_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("deploy")).unwrap();
+        std::fs::write(dir.join("deploy.rs"), "pub mod aws;\npub fn run() {}\n").unwrap();
+        std::fs::write(dir.join("deploy").join("aws.rs"), "pub fn upload() {}\n").unwrap();
+
+        let mut module: ItemMod = parse_quote! {
+            pub mod cli {
+                pub mod deploy;
+            }
+        };
+        let mut files = InlinedFiles::default();
+        inline_submodules(&mut module, &dir, &dir, Path::new("src/main.rs"), &mut files).unwrap();
+        assert_eq!(
+            files.paths().collect::<Vec<_>>(),
+            [dir.join("deploy.rs"), dir.join("deploy").join("aws.rs")]
+        );
+
+        let items = &module.content.as_ref().unwrap().1;
+        let syn::Item::Mod(deploy) = &items[0] else { panic!("expected module") };
+        let deploy_items = &deploy.content.as_ref().unwrap().1;
+        assert!(deploy.semi.is_none());
+        assert!(matches!(&deploy_items[1], syn::Item::Mod(aws) if aws.content.is_some()));
+        assert!(matches!(&deploy_items[2], syn::Item::Fn(f) if f.sig.ident == "run"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_error_position_in_module_file() {
+        let dir = std::env::temp_dir().join(format!("tusks_file_positions_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("deploy.rs"), "pub fn run() {}\n\npub fn stop() {}\n").unwrap();
+        std::fs::write(dir.join("broken.rs"), "pub fn run() {}\n\npub fn 3() {}\n").unwrap();
+
+        let mut module: ItemMod = parse_quote! {
+            pub mod cli {
+                pub mod deploy;
+            }
+        };
+        let mut files = InlinedFiles::default();
+        inline_submodules(&mut module, &dir, &dir, Path::new("src/main.rs"), &mut files).unwrap();
+
+        // The tokens of the file keep their positions, so errors name the file and line
+        let items = &module.content.as_ref().unwrap().1;
+        let syn::Item::Mod(deploy) = &items[0] else { panic!("expected module") };
+        let syn::Item::Fn(stop) = &deploy.content.as_ref().unwrap().1[2] else {
+            panic!("expected function")
+        };
+        let error = files.locate(syn::Error::new_spanned(&stop.sig.ident, "invalid command"));
+        assert_eq!(
+            error.to_string(),
+            format!("invalid command (in `{}:3:8`)", dir.join("deploy.rs").display())
+        );
+
+        // Errors outside the module files are left as they are
+        let error = files.locate(syn::Error::new_spanned(&module.ident, "invalid module"));
+        assert_eq!(error.to_string(), "invalid module");
+
+        let mut module: ItemMod = parse_quote! {
+            pub mod cli {
+                pub mod broken;
+            }
+        };
+        let mut files = InlinedFiles::default();
+        let error = inline_submodules(&mut module, &dir, &dir, Path::new("src/main.rs"), &mut files)
+            .unwrap_err();
+        assert!(error.to_string().contains(&format!("`{}:3:", dir.join("broken.rs").display())));
+        assert_eq!(files.paths().collect::<Vec<_>>(), [dir.join("broken.rs")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_error_position_by_declaration() {
+        // Like inside the compiler: the tokens of each file carry the span of its declaration,
+        // the tokens of `nested.rs` the one of `deploy.rs` it is declared in
+        let declarations: syn::File = syn::parse_str("pub mod deploy;\npub mod stop;").unwrap();
+        let deploy = declarations.items[0].span();
+        let stop = declarations.items[1].span();
+        let file = |path: &str, declaration: Span| InlinedFile {
+            path: PathBuf::from(path),
+            anchor: declaration,
+            declaration: SpanRange::of(declaration),
+            located: false,
+        };
+        let files = InlinedFiles {
+            files: vec![file("deploy.rs", deploy), file("deploy/nested.rs", deploy), file("stop.rs", stop)],
+        };
+
+        assert_eq!(files.position(stop).as_deref(), Some("`stop.rs`"));
+        assert_eq!(files.position(deploy).as_deref(), Some("`deploy.rs` or `deploy/nested.rs`"));
+        assert_eq!(files.position(Span::call_site()), None);
+    }
+}
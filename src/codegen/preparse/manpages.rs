@@ -0,0 +1,32 @@
+use syn::{Item, ItemFn, ItemMod, parse_quote};
+
+/// Adds the hidden `manpages <dir>` command of `#[tusks(manpages(command))]`, which writes the
+/// man pages via `write_manpages` and prints the written files.
+///
+/// # Example
+/// ```ignore
+/// mytool manpages target/man && install -m 644 target/man/*.1 /usr/share/man/man1
+/// ```
+pub fn add_manpages_function(module: &mut ItemMod) {
+    let function: ItemFn = parse_quote! {
+        #[command(about = "Write the man pages to a directory", name = "manpages", hide = true)]
+        pub fn _manpages(#[arg()] dir: ::std::path::PathBuf) -> Option<u8> {
+            match __internal_tusks_module::manpages::write_all(&dir) {
+                Ok(files) => {
+                    for file in files {
+                        println!("{}", file.display());
+                    }
+                    Some(0)
+                }
+                Err(error) => {
+                    eprintln!("error: failed to write the man pages to {}: {}", dir.display(), error);
+                    Some(1)
+                }
+            }
+        }
+    };
+
+    if let Some((_, ref mut items)) = module.content {
+        items.push(Item::Fn(function));
+    }
+}
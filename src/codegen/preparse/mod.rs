@@ -1 +1,4 @@
 pub mod tasks;
+pub mod file_modules;
+pub mod completions;
+pub mod manpages;
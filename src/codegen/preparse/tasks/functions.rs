@@ -1,7 +1,9 @@
 use syn::{Item, ItemFn, ItemMod, parse_quote};
 use syn::{Attribute, Meta};
 
-use crate::{AttributeValue, attribute::models::TasksConfig};
+use crate::{AttributeValue, TusksModule, attribute::models::TasksConfig};
+use crate::codegen::util::command_attribute::{command_name, is_hidden_command};
+use crate::parsing::util::diagnostics::Diagnostics;
 
 pub fn add_use_staements(module: &mut ItemMod) {
     let use_statement: Item = parse_quote! {
@@ -127,3 +129,192 @@ pub fn add_show_help_for_task(module: &mut ItemMod, config: &TasksConfig) {
         items.push(Item::Fn(function));
     }
 }
+
+/// Adds the hidden `__complete-tasks [prefix]` command, which prints the dotted task names
+/// (e.g. `build.release`) starting with `prefix`, one per line. Commands deeper than `max_depth`
+/// are completed as their group at the last level.
+///
+/// `__complete-tasks --shell <bash|zsh|fish>` prints the shell code completing the first
+/// argument with these names, which `completions <shell>` appends to its script as well.
+/// Bash sorts the names itself, keeping their order would need `complete -o nosort` of bash 4.4.
+///
+/// # Example
+/// ```ignore
+/// eval "$(mytool __complete-tasks --shell bash)"
+/// ```
+pub fn add_complete_task_function(module: &mut ItemMod, config: &TasksConfig) {
+    let separator = &config.separator;
+    let max_depth = &config.max_depth;
+
+    let script_function: ItemFn = parse_quote! {
+        /// The shell code completing the first argument of `name` with the task names
+        #[doc(hidden)]
+        fn _task_completion_script(shell: &str, name: &str) -> Option<String> {
+            let function = format!("_{}_tasks", name.replace('-', "_"));
+            let script = match shell {
+                "bash" => format!(
+                    r#"
+{function}() {{
+    if [ "$COMP_CWORD" -eq 1 ] && [[ "${{COMP_WORDS[1]}}" != -* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($({name} __complete-tasks -- "${{COMP_WORDS[1]}}" 2>/dev/null))
+        return 0
+    fi
+    if declare -F _{name} >/dev/null; then
+        _{name} "$@"
+    fi
+}}
+complete -o bashdefault -o default -F {function} {name}
+"#
+                ),
+                "zsh" => format!(
+                    r#"
+{function}() {{
+    if (( CURRENT == 2 )) && [[ "${{words[2]}}" != -* ]]; then
+        local -a tasks
+        tasks=(${{(f)"$({name} __complete-tasks -- "${{words[2]}}" 2>/dev/null)"}})
+        compadd -Q -a tasks
+    elif (( $+functions[_{name}] )); then
+        _{name} "$@"
+    else
+        _files
+    fi
+}}
+compdef {function} {name}
+"#
+                ),
+                "fish" => format!(
+                    r#"
+complete -c {name} -f -n '__fish_is_first_arg; and not string match -q -- "-*" (commandline -ct)' -a '({name} __complete-tasks -- (commandline -ct))'
+"#
+                ),
+                _ => return None,
+            };
+            Some(script)
+        }
+    };
+
+    let function: ItemFn = parse_quote! {
+        #[command(about = "Complete a task name", name = "__complete-tasks", hide = true)]
+        pub fn _complete_task(
+            #[arg()] prefix: Option<String>,
+            #[arg(long, value_parser = ["bash", "zsh", "fish"])] shell: Option<String>
+        ) -> Option<u8> {
+            use ::std::io::Write as _;
+
+            let command = <__internal_tusks_module::cli::Cli as ::tusks::clap::CommandFactory>::command();
+
+            if let Some(shell) = shell {
+                let script = _task_completion_script(&shell, command.get_name())?;
+                return Some(::std::io::stdout().write_all(script.as_bytes()).map_or(1, |_| 0));
+            }
+
+            fn tree(command: &::tusks::clap::Command) -> ::tusks::task_names::TaskCommand {
+                ::tusks::task_names::TaskCommand {
+                    name: command.get_name().to_string(),
+                    hidden: command.is_hide_set(),
+                    subcommands: command.get_subcommands().map(tree).collect(),
+                }
+            }
+
+            let names = ::tusks::task_names::task_names(&tree(&command), #separator, #max_depth);
+
+            let prefix = prefix.unwrap_or_default();
+            let mut output = String::new();
+            for name in names.iter().filter(|name| name.starts_with(&prefix)) {
+                output.push_str(name);
+                output.push('\n');
+            }
+            Some(::std::io::stdout().write_all(output.as_bytes()).map_or(1, |_| 0))
+        }
+    };
+
+    if let Some((_, ref mut items)) = module.content {
+        items.push(Item::Fn(script_function));
+        items.push(Item::Fn(function));
+    }
+}
+
+/// Checks that no visible command name contains the task separator, as the dotted task names
+/// (e.g. `db.run-migrations`) are split at the separator to find the commands again.
+/// The names are taken after applying `rename_all`, so e.g. `separator = "_"` works with the
+/// default kebab-case names but not with `rename_all = "snake_case"`.
+///
+/// # Errors
+/// Returns an error for every command whose name contains the separator
+pub fn validate_task_names(module: &TusksModule, config: &TasksConfig) -> syn::Result<()> {
+    let mut diagnostics = Diagnostics::new();
+    collect_task_name_errors(module, config, &mut diagnostics);
+    diagnostics.finish()
+}
+
+fn collect_task_name_errors(
+    module: &TusksModule,
+    config: &TasksConfig,
+    diagnostics: &mut Diagnostics
+) {
+    let separator = &config.separator;
+    let check = |ident: &syn::Ident, command_attrs: &[&Attribute]| {
+        let name = command_name(ident, module.rename_all, command_attrs);
+        if is_hidden_command(command_attrs) || !name.contains(separator.as_str()) {
+            return Ok(());
+        }
+        Err(syn::Error::new_spanned(
+            ident,
+            format!(
+                "command name `{}` contains the task separator `{}`: \
+                    choose another separator or rename the command",
+                name,
+                separator
+            )
+        ))
+    };
+
+    for tusk in &module.tusks {
+        diagnostics.check(check(&tusk.func.sig.ident, &tusk.extract_attributes(&["command"])));
+    }
+
+    for external_module in &module.external_modules {
+        diagnostics.check(
+            check(&external_module.alias, &external_module.extract_attributes(&["command"]))
+        );
+    }
+
+    for submodule in &module.submodules {
+        diagnostics.check(check(&submodule.name, &submodule.extract_attributes(&["command"])));
+        collect_task_name_errors(submodule, config, diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_task_function_is_a_hidden_command() {
+        let mut module: ItemMod = parse_quote! {
+            pub mod tasks {
+                pub fn build() {}
+            }
+        };
+        let config = TasksConfig {
+            max_groupsize: 5,
+            max_depth: 5,
+            separator: ".".to_string(),
+            use_colors: false,
+        };
+        add_complete_task_function(&mut module, &config);
+
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        let commands: Vec<String> = tusks_module.tusks.iter()
+            .map(|tusk| tusk.func.sig.ident.to_string())
+            .collect();
+        // The script function is private, so it is no command
+        assert_eq!(commands, ["build", "_complete_task"]);
+
+        let complete_task = &tusks_module.tusks[1];
+        let command_attrs = complete_task.extract_attributes(&["command"]);
+        assert!(is_hidden_command(&command_attrs));
+        assert_eq!(command_name(&complete_task.func.sig.ident, None, &command_attrs), "__complete-tasks");
+    }
+}
@@ -0,0 +1,107 @@
+use syn::{Attribute, Expr, ExprLit, Field, Lit, Meta, Token, punctuated::Punctuated};
+
+use crate::attribute::models::RenameRule;
+use crate::codegen::util::enum_util::ident_name;
+
+/// The id of the argument clap derives from a Parameters field: the value of `id = "..."` (or
+/// the older `name = "..."`) in the `#[arg(...)]` attributes, the name of the field otherwise.
+///
+/// Returns `None` for fields without an argument of their own, i.e. `#[arg(skip)]` and
+/// `#[command(flatten)]` fields.
+pub fn arg_id(field: &Field) -> Option<String> {
+    let field_name = ident_name(field.ident.as_ref()?);
+    let arguments = arg_arguments(&field.attrs);
+
+    if arguments.iter().any(|meta| meta.path().is_ident("skip")) || is_flattened(&field.attrs) {
+        return None;
+    }
+
+    Some(string_value(&arguments, "id")
+        .or_else(|| string_value(&arguments, "name"))
+        .unwrap_or(field_name))
+}
+
+/// The long option of the argument of a Parameters field: the value of `long = "..."`, or the
+/// field name in kebab-case for a plain `long`
+pub fn long_name(field: &Field) -> Option<String> {
+    let field_name = ident_name(field.ident.as_ref()?);
+    arg_arguments(&field.attrs).iter().find_map(|meta| match meta {
+        Meta::Path(path) if path.is_ident("long") => Some(RenameRule::KebabCase.apply(&field_name)),
+        Meta::NameValue(name_value) if name_value.path.is_ident("long") => match &name_value.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(long), .. }) => Some(long.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The comma separated arguments of all `#[arg(...)]` attributes
+pub fn arg_arguments(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident("arg"))
+        .filter_map(|attr| attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .collect()
+}
+
+/// Checks if the field is a `#[command(flatten)]` group of arguments
+fn is_flattened(attrs: &[Attribute]) -> bool {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident("command"))
+        .filter_map(|attr| attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .any(|meta| meta.path().is_ident("flatten"))
+}
+
+/// The string value of `key = "..."`
+fn string_value(arguments: &[Meta], key: &str) -> Option<String> {
+    arguments.iter().find_map(|meta| match meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident(key) => match &name_value.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(value), .. }) => Some(value.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ItemStruct, parse_quote};
+
+    fn field(item_struct: ItemStruct) -> Field {
+        item_struct.fields.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_arg_id() {
+        assert_eq!(arg_id(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(long)] pub r#type: &'a String }
+        })).as_deref(), Some("type"));
+        assert_eq!(arg_id(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(long, id = "target")] pub region: &'a String }
+        })).as_deref(), Some("target"));
+        assert_eq!(arg_id(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(name = "target")] pub region: &'a String }
+        })).as_deref(), Some("target"));
+        assert_eq!(arg_id(&field(parse_quote! {
+            pub struct Parameters { #[arg(skip)] pub cache: Vec<String> }
+        })), None);
+        assert_eq!(arg_id(&field(parse_quote! {
+            pub struct Parameters { #[command(flatten)] pub common: Common }
+        })), None);
+    }
+
+    #[test]
+    fn test_long_name() {
+        assert_eq!(long_name(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(short, long)] pub dry_run: &'a bool }
+        })).as_deref(), Some("dry-run"));
+        assert_eq!(long_name(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(long = "config")] pub settings: &'a String }
+        })).as_deref(), Some("config"));
+        assert_eq!(long_name(&field(parse_quote! {
+            pub struct Parameters<'a> { #[arg(short)] pub config: &'a String }
+        })), None);
+    }
+}
@@ -1,49 +1,163 @@
-use syn::Attribute;
+use syn::{Attribute, Meta, Token, punctuated::Punctuated};
 
 use crate::{TusksModule, models::{ExternalModule, Tusk, TusksParameters}};
 
 impl TusksParameters {
     pub fn extract_attributes<'a>(&'a self, names: &[&str]) -> Vec<&'a Attribute> {
-        self
-            .pstruct
-            .attrs
-            .iter()
-            .filter(|attr| {
-                attr.path().get_ident()
-                    .map(|ident| names.contains(&ident.to_string().as_str()))
-                    .unwrap_or(false)
-            })
-            .collect()
+        filter_named_attributes(&self.pstruct.attrs, names)
     }
 
 }
 
 impl Tusk {
     pub fn extract_attributes<'a>(&'a self, names: &[&str]) -> Vec<&'a Attribute> {
-        self.func.attrs.iter().filter(|attr| {
-            attr.path().get_ident()
-                .map(|ident| names.contains(&ident.to_string().as_str()))
-                .unwrap_or(false)
-        }).collect()
+        filter_named_attributes(&self.func.attrs, names)
     }
 }
 
 impl ExternalModule {
     pub fn extract_attributes<'a>(&'a self, names: &[&str]) -> Vec<&'a Attribute> {
-        self.item_use.attrs.iter().filter(|attr| {
-            attr.path().get_ident()
-                .map(|ident| names.contains(&ident.to_string().as_str()))
-                .unwrap_or(false)
-        }).collect()
+        filter_named_attributes(&self.item_use.attrs, names)
     }
 }
 
 impl TusksModule {
     pub fn extract_attributes<'a>(&'a self, names: &[&str]) -> Vec<&'a Attribute> {
-        self.attrs.0.iter().filter(|attr| {
-            attr.path().get_ident()
-                .map(|ident| names.contains(&ident.to_string().as_str()))
-                .unwrap_or(false)
-        }).collect()
+        filter_named_attributes(&self.attrs.0, names)
+    }
+}
+
+/// The attributes with one of the names, see `is_named_attribute`
+fn filter_named_attributes<'a>(attrs: &'a [Attribute], names: &[&str]) -> Vec<&'a Attribute> {
+    attrs.iter().filter(|attr| is_named_attribute(attr, names)).collect()
+}
+
+/// Checks if the attribute has one of the names and carries arguments. Bare attributes like
+/// `#[command]` only mark an item as a command in explicit mode, clap does not accept them.
+///
+/// `#[cfg_attr(predicate, ...)]` counts if it only wraps such attributes, so it is forwarded
+/// as a whole and e.g. `#[cfg_attr(feature = "x", command(name = "up"))]` renames the
+/// generated command only with the feature.
+pub fn is_named_attribute(attr: &Attribute, names: &[&str]) -> bool {
+    if attr.path().is_ident("cfg_attr") {
+        return cfg_attr_arguments(attr).is_some_and(|(_, attrs)| {
+            !attrs.is_empty() && attrs.iter().all(|meta| is_named_meta(meta, names))
+        });
+    }
+    is_named_meta(&attr.meta, names)
+}
+
+fn is_named_meta(meta: &Meta, names: &[&str]) -> bool {
+    !matches!(meta, Meta::Path(_))
+        && meta.path().get_ident().is_some_and(|ident| names.contains(&ident.to_string().as_str()))
+}
+
+/// The predicate and the wrapped attributes of `#[cfg_attr(predicate, attrs...)]`
+pub fn cfg_attr_arguments(attr: &Attribute) -> Option<(Meta, Vec<Meta>)> {
+    if !attr.path().is_ident("cfg_attr") {
+        return None;
+    }
+    let mut arguments = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .ok()?
+        .into_iter();
+    let predicate = arguments.next()?;
+    Some((predicate, arguments.collect()))
+}
+
+/// Extract the `#[cfg(...)]` attributes. They are copied onto everything generated for an item
+/// (enum variants, fields, match arms), so that the generated code disappears together with the
+/// item. `#[cfg_attr(...)]` is not copied along, the attributes it adds belong to the item or
+/// are forwarded with the attributes they wrap, see `is_named_attribute`.
+pub fn extract_cfg_attributes(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("cfg")).collect()
+}
+
+impl Tusk {
+    pub fn cfg_attributes(&self) -> Vec<&Attribute> {
+        extract_cfg_attributes(&self.func.attrs)
+    }
+}
+
+impl ExternalModule {
+    pub fn cfg_attributes(&self) -> Vec<&Attribute> {
+        extract_cfg_attributes(&self.item_use.attrs)
+    }
+}
+
+impl TusksModule {
+    pub fn cfg_attributes(&self) -> Vec<&Attribute> {
+        extract_cfg_attributes(&self.attrs.0)
+    }
+
+    /// Checks if any command of this module is conditional. The generated matches then need a
+    /// catch-all arm, as their enums may lose variants or even become empty.
+    pub fn has_conditional_commands(&self) -> bool {
+        self.tusks.iter().any(|tusk| !tusk.cfg_attributes().is_empty())
+            || self.submodules.iter().any(|submodule| !submodule.cfg_attributes().is_empty())
+            || self.has_conditional_external_modules()
+    }
+
+    pub fn has_conditional_external_modules(&self) -> bool {
+        self.external_modules.iter().any(|ext_mod| !ext_mod.cfg_attributes().is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use syn::{ItemFn, parse_quote};
+
+    #[test]
+    fn test_extract_cfg_attributes() {
+        let func: ItemFn = parse_quote! {
+            #[cfg(feature = "cloud")]
+            #[cfg_attr(feature = "cloud", command(name = "up"))]
+            #[doc = "Upload"]
+            pub fn upload() {}
+        };
+
+        let cfg_attrs = extract_cfg_attributes(&func.attrs);
+        assert_eq!(quote! { #(#cfg_attrs)* }.to_string(), "# [cfg (feature = \"cloud\")]");
+    }
+
+    #[test]
+    fn test_cfg_propagation() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod cli {
+                #[cfg(feature = "cloud")]
+                #[cfg_attr(feature = "cloud", command(name = "up"))]
+                pub fn upload() {}
+                pub fn status() {}
+            }
+        };
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        assert!(tusks_module.has_conditional_commands());
+
+        let cli = tusks_module.build_cli(Vec::new(), false).to_string();
+        let handle_matches = tusks_module.build_handle_matches(true, None).to_string();
+        // The conditional name goes to the variant, the match arm only needs the cfg
+        assert!(cli.contains(
+            "# [cfg (feature = \"cloud\")] # [cfg_attr (feature = \"cloud\" , command (name = \"up\"))] Upload { }"
+        ));
+        assert!(handle_matches.contains(
+            "# [cfg (feature = \"cloud\")] Some (cli :: Commands :: Upload { }) =>"
+        ));
+        assert!(!handle_matches.contains("cfg_attr"));
+    }
+
+    #[test]
+    fn test_forward_cfg_attr_of_tusks_attributes() {
+        let func: ItemFn = parse_quote! {
+            #[cfg_attr(feature = "cloud", command(name = "up"))]
+            #[cfg_attr(feature = "cloud", command(hide), arg(long))]
+            #[cfg_attr(feature = "cloud", command(name = "up"), derive(Debug))]
+            #[cfg_attr(feature = "cloud", command)]
+            pub fn upload() {}
+        };
+
+        let forwarded = filter_named_attributes(&func.attrs, &["command", "arg"]);
+        assert_eq!(forwarded.len(), 2);
+        assert_eq!(filter_named_attributes(&func.attrs, &["command"]).len(), 1);
     }
 }
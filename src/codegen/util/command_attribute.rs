@@ -1,5 +1,8 @@
 use proc_macro2::TokenStream;
-use syn::{Attribute, parse_quote, spanned::Spanned};
+use syn::{
+    Attribute, Expr, ExprLit, Ident, Lit, Meta, Token,
+    parse_quote, punctuated::Punctuated, spanned::Spanned
+};
 
 use crate::models::{
     Tusk,
@@ -7,7 +10,12 @@ use crate::models::{
     TusksModule
 };
 
-use quote::quote;
+use quote::{quote, quote_spanned};
+
+use crate::attribute::models::RenameRule;
+use crate::codegen::util::attribute::cfg_attr_arguments;
+use crate::codegen::util::enum_util::ident_name;
+use crate::parsing::util::doc_comment::doc_lines_without_arguments_section;
 
 impl TusksModule {
     /// Example 1 - Root module of tusks
@@ -28,8 +36,7 @@ impl TusksModule {
     /// pub enum Commands {
     ///     /// ... other subcommands and submodule-subcommands
     ///     #[command(name = "level1")] /// <===== here =====
-    ///     #[allow(non_camel_case_types)]
-    ///     level1 {
+    ///     Level1 {
     ///         #[arg(long)]
     ///         level1_field: Option<String>,
     ///         #[arg(long, default_value = "42")]
@@ -39,15 +46,19 @@ impl TusksModule {
     ///     },
     /// }
     /// ```
+    ///
+    /// The `///` docs of the module are forwarded in front of the command attribute, so clap
+    /// uses their first paragraph as `about` and the rest as `long_about`.
     pub fn generate_command_attribute(&self) -> TokenStream {
+        let doc_attrs = forward_doc_attributes(self.extract_attributes(&["doc"]));
         let existing_attrs = self.extract_attributes(&["command"]);
 
         if !existing_attrs.is_empty() {
             // Use existing command attribute
-            quote! { #(#existing_attrs)* }
+            quote! { #doc_attrs #(#existing_attrs)* }
         } else {
             // Generate default command attribute
-            quote! { #[command()] }
+            quote! { #doc_attrs #[command()] }
         }
     }
 
@@ -69,8 +80,7 @@ impl TusksModule {
     /// pub enum Commands {
     ///     /// ... other subcommands and submodule-subcommands
     ///     #[command(name = "level1")] /// see generate_command_attribute
-    ///     #[allow(non_camel_case_types)]
-    ///     level1 {
+    ///     Level1 {
     ///         #[arg(long)]
     ///         level1_field: Option<String>,
     ///         #[arg(long, default_value = "42")]
@@ -110,9 +120,26 @@ impl TusksModule {
 }
 
 impl Tusk {
+    /// Example:
+    /// ```ignore
+    /// pub enum Commands {
+    ///     /// Deploy the application  <===== here (docs of the function) =====
+    ///     #[command()]             <===== here =====
+    ///     Deploy { ... },
+    /// }
+    /// ```
+    ///
+    /// The `# Arguments` section of the docs is left out, it becomes the help of the arguments.
     pub fn generate_command_attribute(&self) -> TokenStream {
+        let doc_attrs = doc_lines_without_arguments_section(&self.func.attrs)
+            .into_iter()
+            .map(|line| {
+                let text = syn::LitStr::new(&line.text, line.span);
+                quote_spanned! {line.span=> #[doc = #text] }
+            });
         let existing_attrs = self.extract_attributes(&["command"]);
-        use_attributes_or_default(&existing_attrs, quote! { #[command()] })
+        let command_attrs = use_attributes_or_default(&existing_attrs, quote! { #[command()] });
+        quote! { #(#doc_attrs)* #command_attrs }
     }
 }
 
@@ -121,16 +148,100 @@ impl ExternalModule {
     /// ```rust
     /// pub enum ExternalCommands {
     ///    #[command(name = "ext2")]
-    ///    #[allow(non_camel_case_types)]
-    ///    ext2(super::super::ext2::__internal_tusks_module::cli::Cli),
+    ///    Ext2(super::super::ext2::__internal_tusks_module::cli::Cli),
     ///}
     ///```
     pub fn generate_command_attribute(&self) -> TokenStream {
+        let doc_attrs = forward_doc_attributes(self.extract_attributes(&["doc"]));
         let existing_attrs = self.extract_attributes(&["command"]);
-        use_attributes_or_default(&existing_attrs, quote! { #[command()] })
+        let command_attrs = use_attributes_or_default(&existing_attrs, quote! { #[command()] });
+        quote! { #doc_attrs #command_attrs }
     }
 }
 
+/// Generate `#[command(name = "...")]` for a command named after `ident` if a naming convention
+/// is set and the name is not given explicitly via `#[command(name = "...")]`.
+/// Without a convention nothing is generated and clap derives the kebab-case name from the
+/// variant.
+///
+/// # Example
+/// For `fn run_all_tests` with `rename_all = "snake_case"`:
+/// ```ignore
+/// #[command(name = "run_all_tests")]
+/// RunAllTests { ... },
+/// ```
+pub fn generate_command_name_attribute(
+    ident: &Ident,
+    rename_all: Option<RenameRule>,
+    command_attrs: &[&Attribute]
+) -> TokenStream {
+    let Some(rename_all) = rename_all else {
+        return quote! {};
+    };
+
+    if explicit_command_name(command_attrs).is_some() {
+        return quote! {};
+    }
+
+    let name = rename_all.apply(&ident_name(ident));
+    quote_spanned! {ident.span()=> #[command(name = #name)] }
+}
+
+/// The name of the command named after `ident` as typed on the command line
+pub fn command_name(
+    ident: &Ident,
+    rename_all: Option<RenameRule>,
+    command_attrs: &[&Attribute]
+) -> String {
+    explicit_command_name(command_attrs).unwrap_or_else(|| {
+        rename_all.unwrap_or(RenameRule::KebabCase).apply(&ident_name(ident))
+    })
+}
+
+/// Checks if `hide` is set in the `#[command(...)]` attributes
+pub fn is_hidden_command(command_attrs: &[&Attribute]) -> bool {
+    command_attrs.iter().flat_map(|attr| command_arguments(attr)).any(|meta| match meta {
+        Meta::Path(path) => path.is_ident("hide"),
+        Meta::NameValue(name_value) => name_value.path.is_ident("hide") && matches!(
+            &name_value.value,
+            Expr::Lit(ExprLit { lit: Lit::Bool(value), .. }) if value.value
+        ),
+        Meta::List(_) => false,
+    })
+}
+
+/// The value of `name = "..."` in the `#[command(...)]` attributes
+fn explicit_command_name(command_attrs: &[&Attribute]) -> Option<String> {
+    command_attrs.iter().flat_map(|attr| command_arguments(attr)).find_map(|meta| match meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident("name") => match name_value.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(name), .. }) => Some(name.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The comma separated arguments of a `#[command(...)]` attribute. Arguments behind
+/// `#[cfg_attr(...)]` are left out, as it is unknown here whether they apply.
+fn command_arguments(attr: &Attribute) -> Vec<Meta> {
+    if attr.path().is_ident("cfg_attr") {
+        return Vec::new();
+    }
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .map(|args| args.into_iter().collect())
+        .unwrap_or_default()
+}
+
+/// Forward `#[doc]` attributes (`///` comments) to a generated command.
+/// clap derive turns the first paragraph into `about` and the rest into `long_about`,
+/// unless they are set explicitly via `#[command(about = ...)]`.
+fn forward_doc_attributes(doc_attrs: Vec<&Attribute>) -> TokenStream {
+    // Only `#[doc = "..."]` carries text, `#[doc(hidden)]` and the like are not forwarded
+    let doc_attrs = doc_attrs.into_iter()
+        .filter(|attr| matches!(attr.meta, syn::Meta::NameValue(_)));
+    quote! { #(#doc_attrs)* }
+}
+
 fn use_attributes_or_default(attrs: &[&Attribute], default: TokenStream) -> TokenStream {
     if !attrs.is_empty() {
         quote! { #(#attrs)* }
@@ -150,16 +261,35 @@ fn use_attributes_or_default(attrs: &[&Attribute], default: TokenStream) -> Toke
 /// # Examples
 /// * `#[subcommands(arg1, arg2)]` with target "subcommand" → `#[command(subcommand, arg1, arg2)]`
 /// * `#[external_subcommands]` with target "flatten" → `#[command(flatten)]`
+/// * `#[cfg_attr(unix, subcommands(arg1))]` with target "subcommand" →
+///   `#[command(subcommand)] #[cfg_attr(unix, command(arg1))]`
 fn transform_attributes_to_command(attrs: Vec<&syn::Attribute>, target_keyword: &str) -> TokenStream {
     let mut result = TokenStream::new();
+    let mut conditional = TokenStream::new();
     let mut target_ident = syn::Ident::new(target_keyword, proc_macro2::Span::call_site());
     
     for attr in attrs {
+        target_ident.set_span(attr.span());
+
+        // #[cfg_attr(predicate, subcommands(params))] → #[cfg_attr(predicate, command(params))],
+        // the keyword comes from an unconditional attribute
+        if let Some((predicate, wrapped)) = cfg_attr_arguments(attr) {
+            let wrapped: Vec<_> = wrapped.iter().filter_map(|meta| match meta {
+                Meta::List(meta_list) => {
+                    let inner_tokens = &meta_list.tokens;
+                    Some(quote! { command(#inner_tokens) })
+                }
+                _ => None,
+            }).collect();
+            if !wrapped.is_empty() {
+                conditional.extend(quote! { #[cfg_attr(#predicate, #(#wrapped),*)] });
+            }
+            continue;
+        }
+
         let pound_span = attr.pound_token.span;
         let bracket_span = attr.bracket_token.span;
 
-        target_ident.set_span(attr.span());
-        
         // Parse the tokens inside the attribute
         if let syn::Meta::List(meta_list) = &attr.meta {
             let inner_tokens = &meta_list.tokens;
@@ -189,5 +319,9 @@ fn transform_attributes_to_command(attrs: Vec<&syn::Attribute>, target_keyword:
         }
     }
     
+    if result.is_empty() {
+        result.extend(quote! { #[command(#target_ident)] });
+    }
+    result.extend(conditional);
     result
 }
@@ -1,19 +1,133 @@
 use syn::Ident;
 
+use crate::attribute::models::RenameRule;
+
 /// Convert a function name to an enum variant name
 /// Example: "my_tusk_function" -> "MyTuskFunction"
 pub fn convert_function_to_enum_variant(func_name: &Ident) -> Ident {
-    func_name.clone()
+    to_variant_ident(func_name)
 }
 
 /// Convert a submodule name to an enum variant name
 /// Example: "sub_module" -> "SubModule"
 pub fn convert_submodule_to_enum_variant(submod_name: &Ident) -> Ident {
-    submod_name.clone()
+    to_variant_ident(submod_name)
 }
 
 /// Convert an external module name to an enum variant name
 /// Example: "my_lib" -> "MyLib"
 pub fn convert_external_module_to_enum_variant(alias: &Ident) -> Ident {
-    alias.clone()
+    to_variant_ident(alias)
+}
+
+/// Build a PascalCase identifier with the span of `ident`.
+/// Leading underscores are kept, so `_execute_task` and `execute_task` stay distinct.
+fn to_variant_ident(ident: &Ident) -> Ident {
+    let name = ident_name(ident);
+    let underscores = name.len() - name.trim_start_matches('_').len();
+    let pascal = RenameRule::PascalCase.apply(&name);
+    Ident::new(&format!("{}{}", "_".repeat(underscores), pascal), ident.span())
+}
+
+/// The name of an identifier without the `r#` prefix of raw identifiers
+pub fn ident_name(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.strip_prefix("r#").map(str::to_string).unwrap_or(name)
+}
+
+impl RenameRule {
+    /// Apply the naming convention to a name
+    ///
+    /// # Examples
+    /// * kebab-case: "run_all_tests" → "run-all-tests"
+    /// * PascalCase: "run_all_tests" → "RunAllTests"
+    /// * camelCase: "run_all_tests" → "runAllTests"
+    pub fn apply(&self, name: &str) -> String {
+        let words = split_words(name);
+
+        match self {
+            RenameRule::KebabCase => join_words(&words, "-", str::to_lowercase),
+            RenameRule::SnakeCase => join_words(&words, "_", str::to_lowercase),
+            RenameRule::ScreamingSnakeCase => join_words(&words, "_", str::to_uppercase),
+            RenameRule::Lower => join_words(&words, "", str::to_lowercase),
+            RenameRule::Upper => join_words(&words, "", str::to_uppercase),
+            RenameRule::PascalCase => join_words(&words, "", capitalize),
+            RenameRule::CamelCase => {
+                let pascal = join_words(&words, "", capitalize);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => pascal,
+                }
+            }
+            RenameRule::Verbatim => name.to_string(),
+        }
+    }
+}
+
+/// Split a name into words at underscores, dashes and lowercase-to-uppercase boundaries
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_is_lower = false;
+
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_is_lower = false;
+            continue;
+        }
+
+        if c.is_uppercase() && previous_is_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+
+        previous_is_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn join_words(words: &[String], separator: &str, convert: fn(&str) -> String) -> String {
+    words.iter().map(|word| convert(word)).collect::<Vec<_>>().join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    #[test]
+    fn test_rename_rules() {
+        assert_eq!(RenameRule::KebabCase.apply("run_all_tests"), "run-all-tests");
+        assert_eq!(RenameRule::SnakeCase.apply("runAllTests"), "run_all_tests");
+        assert_eq!(RenameRule::ScreamingSnakeCase.apply("run_all"), "RUN_ALL");
+        assert_eq!(RenameRule::CamelCase.apply("run_all_tests"), "runAllTests");
+        assert_eq!(RenameRule::PascalCase.apply("build2_release"), "Build2Release");
+        assert_eq!(RenameRule::Lower.apply("run_all"), "runall");
+        assert_eq!(RenameRule::Verbatim.apply("run_all"), "run_all");
+    }
+
+    #[test]
+    fn test_variant_idents() {
+        let ident = |name| Ident::new(name, Span::call_site());
+        assert_eq!(convert_function_to_enum_variant(&ident("run_all_tests")), "RunAllTests");
+        assert_eq!(convert_function_to_enum_variant(&ident("_execute_task")), "_ExecuteTask");
+        assert_eq!(convert_submodule_to_enum_variant(&Ident::new_raw("type", Span::call_site())), "Type");
+    }
 }
@@ -1,3 +1,7 @@
 pub mod enum_util;
 pub mod attribute;
+pub mod arg_attribute;
 pub mod command_attribute;
+pub mod user_path;
+pub mod type_mapping;
+pub mod warning;
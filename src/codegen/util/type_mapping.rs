@@ -0,0 +1,252 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Lifetime, Type, parse_quote};
+
+use crate::models::StdImports;
+
+/// Maps the type of a command argument or Parameters field to the owned type of the clap field.
+/// Borrowed types map to their owned counterparts, other references are dereferenced.
+/// `Path` and `OsStr` are only known as std types if written as `std::path::Path` or
+/// `std::ffi::OsStr`, or if `imports` holds them, i.e. the module imports them from std.
+///
+/// # Examples
+/// * `&str` → `String`
+/// * `&Path` → `PathBuf` (with `use std::path::Path;`)
+/// * `&[T]` → `Vec<T>`
+/// * `&OsStr` → `OsString` (with `use std::ffi::OsStr;`)
+/// * `Option<&str>` → `Option<String>`
+/// * `&Option<u32>` → `Option<u32>`
+/// * `u32` → `u32`
+pub fn owned_type(ty: &Type, imports: &StdImports) -> Type {
+    if let Some(inner) = option_reference_type(ty) {
+        let owned = owned_type(inner, imports);
+        return parse_quote! { Option<#owned> };
+    }
+
+    let Type::Reference(type_ref) = ty else {
+        return ty.clone();
+    };
+
+    match BorrowedKind::of(&type_ref.elem, imports) {
+        Some(BorrowedKind::Str) => parse_quote! { String },
+        Some(BorrowedKind::Path) => parse_quote! { ::std::path::PathBuf },
+        Some(BorrowedKind::OsStr) => parse_quote! { ::std::ffi::OsString },
+        Some(BorrowedKind::Slice(elem)) => parse_quote! { Vec<#elem> },
+        None => (*type_ref.elem).clone(),
+    }
+}
+
+/// Builds the expression passing a parsed clap argument as a value of type `ty`.
+/// `arg_ref` is a reference to the clap field, whose type is `owned_type(ty, imports)`.
+///
+/// # Examples
+/// For `arg_ref` = `p1`:
+/// * `&str` → `p1.as_str()`
+/// * `&Path` → `p1.as_path()`
+/// * `&[T]` → `p1.as_slice()`
+/// * `&OsStr` → `p1.as_os_str()`
+/// * `Option<&str>` → `p1.as_deref()`
+/// * `&u32` → `p1`
+/// * `u32` → `p1.clone()`
+pub fn borrow_back(ty: &Type, arg_ref: TokenStream, imports: &StdImports) -> TokenStream {
+    if let Some(inner) = option_reference_type(ty) {
+        return if matches!(inner, Type::Reference(r) if BorrowedKind::of(&r.elem, imports).is_some()) {
+            quote! { (#arg_ref).as_deref() }
+        } else {
+            quote! { (#arg_ref).as_ref() }
+        };
+    }
+
+    let Type::Reference(type_ref) = ty else {
+        return quote! { (#arg_ref).clone() };
+    };
+
+    match BorrowedKind::of(&type_ref.elem, imports) {
+        Some(BorrowedKind::Str) => quote! { (#arg_ref).as_str() },
+        Some(BorrowedKind::Path) => quote! { (#arg_ref).as_path() },
+        Some(BorrowedKind::OsStr) => quote! { (#arg_ref).as_os_str() },
+        Some(BorrowedKind::Slice(_)) => quote! { (#arg_ref).as_slice() },
+        None => arg_ref,
+    }
+}
+
+/// Gives the references in `ty` without a lifetime (or with `'_`) the lifetime `lifetime`,
+/// including references nested in generic arguments, tuples, slices and arrays.
+///
+/// # Examples
+/// For `lifetime` = `'a`:
+/// * `&String` → `&'a String`
+/// * `Option<&'_ str>` → `Option<&'a str>`
+/// * `&'b str` → `&'b str`
+pub fn add_elided_lifetimes(ty: &mut Type, lifetime: &Lifetime) {
+    match ty {
+        Type::Reference(type_ref) => {
+            if type_ref.lifetime.as_ref().is_none_or(|existing| existing.ident == "_") {
+                type_ref.lifetime = Some(lifetime.clone());
+            }
+            add_elided_lifetimes(&mut type_ref.elem, lifetime);
+        }
+        Type::Path(type_path) => {
+            for segment in &mut type_path.path.segments {
+                if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        if let syn::GenericArgument::Type(arg_ty) = arg {
+                            add_elided_lifetimes(arg_ty, lifetime);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Tuple(tuple) => {
+            tuple.elems.iter_mut().for_each(|elem| add_elided_lifetimes(elem, lifetime));
+        }
+        Type::Slice(slice) => add_elided_lifetimes(&mut slice.elem, lifetime),
+        Type::Array(array) => add_elided_lifetimes(&mut array.elem, lifetime),
+        Type::Paren(paren) => add_elided_lifetimes(&mut paren.elem, lifetime),
+        Type::Group(group) => add_elided_lifetimes(&mut group.elem, lifetime),
+        _ => {}
+    }
+}
+
+/// Unsized types that are borrowed from an owned counterpart
+enum BorrowedKind<'a> {
+    Str,
+    Path,
+    OsStr,
+    Slice(&'a Type),
+}
+
+impl<'a> BorrowedKind<'a> {
+    fn of(ty: &'a Type, imports: &StdImports) -> Option<Self> {
+        match ty {
+            Type::Slice(slice) => Some(BorrowedKind::Slice(&slice.elem)),
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                let path = &type_path.path;
+                if path.is_ident("str") {
+                    Some(BorrowedKind::Str)
+                } else if is_std_type(path, "path", "Path", imports.path) {
+                    Some(BorrowedKind::Path)
+                } else if is_std_type(path, "ffi", "OsStr", imports.os_str) {
+                    Some(BorrowedKind::OsStr)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `path` names the std type `name` of `module`: `std::path::Path` or
+/// `::std::path::Path` for `path` and `Path`, the bare `Path` only if `imported` from std.
+/// Other types of the same name are left alone.
+fn is_std_type(path: &syn::Path, module: &str, name: &str, imported: bool) -> bool {
+    let segments: Vec<String> = path.segments.iter()
+        .map(|segment| match segment.arguments {
+            syn::PathArguments::None => segment.ident.to_string(),
+            _ => String::new(),
+        })
+        .collect();
+
+    match segments.as_slice() {
+        [ident] => imported && path.leading_colon.is_none() && ident == name,
+        [krate, ident_module, ident] => krate == "std" && ident_module == module && ident == name,
+        _ => false,
+    }
+}
+
+/// Get `&T` for a type `Option<&T>`
+fn option_reference_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner @ Type::Reference(_)) => Some(inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMPORTED: StdImports = StdImports { path: true, os_str: true };
+
+    fn owned(ty: Type) -> String {
+        let owned = owned_type(&ty, &IMPORTED);
+        quote! { #owned }.to_string()
+    }
+
+    fn borrowed(ty: Type) -> String {
+        borrow_back(&ty, quote! { p1 }, &IMPORTED).to_string()
+    }
+
+    #[test]
+    fn test_owned_type() {
+        assert_eq!(owned(parse_quote! { &'a str }), "String");
+        assert_eq!(owned(parse_quote! { &std::path::Path }), ":: std :: path :: PathBuf");
+        assert_eq!(owned(parse_quote! { &[u32] }), "Vec < u32 >");
+        assert_eq!(owned(parse_quote! { Option<&str> }), "Option < String >");
+        assert_eq!(owned(parse_quote! { &'a Option<u32> }), "Option < u32 >");
+        assert_eq!(owned(parse_quote! { u32 }), "u32");
+    }
+
+    #[test]
+    fn test_owned_type_of_std_paths_only() {
+        assert_eq!(owned(parse_quote! { &Path }), ":: std :: path :: PathBuf");
+        assert_eq!(owned(parse_quote! { &::std::path::Path }), ":: std :: path :: PathBuf");
+        assert_eq!(owned(parse_quote! { &std::ffi::OsStr }), ":: std :: ffi :: OsString");
+        assert_eq!(owned(parse_quote! { &crate::routes::Path }), "crate :: routes :: Path");
+        assert_eq!(owned(parse_quote! { &url::Path }), "url :: Path");
+        assert_eq!(owned(parse_quote! { &::Path }), ":: Path");
+        assert_eq!(owned(parse_quote! { &my::ffi::OsStr }), "my :: ffi :: OsStr");
+    }
+
+    #[test]
+    fn test_bare_names_need_std_imports() {
+        // e.g. a `struct Path` of the user, or an import from another crate
+        let not_imported = StdImports::default();
+        let owned = |ty: Type| {
+            let owned = owned_type(&ty, &not_imported);
+            quote! { #owned }.to_string()
+        };
+
+        assert_eq!(owned(parse_quote! { &Path }), "Path");
+        assert_eq!(owned(parse_quote! { Option<&OsStr> }), "Option < OsStr >");
+        assert_eq!(owned(parse_quote! { &std::path::Path }), ":: std :: path :: PathBuf");
+        assert_eq!(borrow_back(&parse_quote! { &Path }, quote! { p1 }, &not_imported).to_string(), "p1");
+    }
+
+    #[test]
+    fn test_borrow_back() {
+        assert_eq!(borrowed(parse_quote! { &str }), "(p1) . as_str ()");
+        assert_eq!(borrowed(parse_quote! { &OsStr }), "(p1) . as_os_str ()");
+        assert_eq!(borrowed(parse_quote! { Option<&Path> }), "(p1) . as_deref ()");
+        assert_eq!(borrowed(parse_quote! { Option<&u32> }), "(p1) . as_ref ()");
+        assert_eq!(borrowed(parse_quote! { &u32 }), "p1");
+        assert_eq!(borrowed(parse_quote! { u32 }), "(p1) . clone ()");
+    }
+
+    #[test]
+    fn test_add_elided_lifetimes() {
+        let with_lifetimes = |mut ty: Type| {
+            add_elided_lifetimes(&mut ty, &parse_quote! { 'a });
+            quote! { #ty }.to_string()
+        };
+
+        assert_eq!(with_lifetimes(parse_quote! { &String }), "& 'a String");
+        assert_eq!(with_lifetimes(parse_quote! { Option<&'_ str> }), "Option < & 'a str >");
+        assert_eq!(with_lifetimes(parse_quote! { &'b [&u32] }), "& 'b [& 'a u32]");
+        assert_eq!(with_lifetimes(parse_quote! { u32 }), "u32");
+    }
+}
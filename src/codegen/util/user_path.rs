@@ -0,0 +1,76 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Ident, Path};
+
+/// Make a path written by the user inside the tusks module at `module_path`
+/// usable from the generated `__internal_tusks_module`, which is a child of the root module.
+///
+/// # Examples
+/// With `module_path` = `["sub"]`:
+/// * `load` → `super::sub::load`
+/// * `self::load` → `super::sub::load`
+/// * `super::load` → `super::load`
+/// * `crate::config::load` → `crate::config::load`
+pub fn resolve_user_path(path: &Path, module_path: &[&str]) -> TokenStream {
+    let is_absolute = path.leading_colon.is_some()
+        || path.segments.first().is_some_and(|segment| segment.ident == "crate");
+    if is_absolute {
+        return quote! { #path };
+    }
+
+    // The root module is `super` from the point of view of __internal_tusks_module
+    let mut base: Vec<Ident> = std::iter::once("super")
+        .chain(module_path.iter().copied())
+        .map(|segment| Ident::new(segment, Span::call_site()))
+        .collect();
+    let mut remaining = path.segments.iter().peekable();
+
+    if remaining.peek().is_some_and(|segment| segment.ident == "self") {
+        remaining.next();
+    }
+
+    while let Some(segment) = remaining.next_if(|segment| segment.ident == "super") {
+        // Leave a named module, or go further up once only `super`s are left
+        if base.last().is_some_and(|last| last != "super") {
+            base.pop();
+        } else {
+            base.push(segment.ident.clone());
+        }
+    }
+
+    let remaining: Vec<_> = remaining.collect();
+    quote! { #(#base::)* #(#remaining)::* }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn resolve(path: Path, module_path: &[&str]) -> String {
+        resolve_user_path(&path, module_path).to_string()
+    }
+
+    #[test]
+    fn test_resolve_user_path() {
+        assert_eq!(resolve(parse_quote! { load }, &[]), "super :: load");
+        assert_eq!(resolve(parse_quote! { load }, &["sub"]), "super :: sub :: load");
+        assert_eq!(resolve(parse_quote! { self::load }, &["sub"]), "super :: sub :: load");
+        assert_eq!(
+            resolve(parse_quote! { config::load }, &["sub"]),
+            "super :: sub :: config :: load"
+        );
+        assert_eq!(resolve(parse_quote! { super::load }, &["sub"]), "super :: load");
+        assert_eq!(resolve(parse_quote! { super::super::load }, &["a", "b"]), "super :: load");
+        assert_eq!(resolve(parse_quote! { super::load }, &[]), "super :: super :: load");
+    }
+
+    #[test]
+    fn test_resolve_absolute_user_path() {
+        assert_eq!(
+            resolve(parse_quote! { crate::config::load }, &["sub"]),
+            "crate :: config :: load"
+        );
+        assert_eq!(resolve(parse_quote! { ::std::fs::read }, &["sub"]), ":: std :: fs :: read");
+    }
+}
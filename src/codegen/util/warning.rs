@@ -0,0 +1,19 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+
+/// Emits a compiler warning at `span`.
+///
+/// Proc macros cannot emit warnings on stable Rust, so the usage of a deprecated item is
+/// generated instead. The compiler reports it as
+/// `warning: use of deprecated unit struct `TusksWarning`: <message>`.
+pub fn build_warning(span: Span, message: &str) -> TokenStream {
+    let usage = quote_spanned! {span=> TusksWarning };
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #message)]
+            struct TusksWarning;
+            let _ = #usage;
+        };
+    }
+}
@@ -0,0 +1,266 @@
+//! Runtime support for root modules with `#[tusks(config_file = "...")]`.
+//!
+//! The generated `config::parse_cli` uses this module (via `::tusks::config`) to read and merge
+//! the config files before parsing the command line. The files are merged in this order, later
+//! files overriding earlier ones key by key:
+//! 1. `$XDG_CONFIG_HOME/<file>` (or `$HOME/.config/<file>`), the user-level file
+//! 2. `./<file>`, the project-local file
+//! 3. the file given via `--config <path>`, which must exist
+//!
+//! The merged values become the default values of the arguments, so environment variables and
+//! the command line still override them.
+
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+/// What a key of the config file configures
+pub enum Key {
+    /// An argument, i.e. a Parameters field
+    Value,
+    /// A submodule, whose keys live in a table of the same name
+    Section,
+}
+
+/// Read and merge the config files named `file_name`, see the module docs for the order.
+/// `key` maps the dotted path of a key (e.g. `deploy.region`) to what it configures.
+///
+/// # Errors
+/// Returns the message for a file that cannot be read or parsed, or that contains a key
+/// `key` does not know.
+pub fn load(
+    file_name: &str,
+    args: &[OsString],
+    key: impl Fn(&str) -> Option<Key>
+) -> Result<Table, String> {
+    load_layers(&layers(file_name, user_config_dir(), config_argument(args)), &key)
+}
+
+/// The values of a config entry as default values of an argument
+pub fn default_values(value: &Value) -> Vec<&'static str> {
+    // Without the `string` feature clap only takes static default values, the few strings of
+    // the config live as long as the program anyway
+    let to_string = |value: &Value| -> &'static str {
+        match value {
+            Value::String(value) => value.clone().leak(),
+            value => value.to_string().leak(),
+        }
+    };
+
+    match value {
+        Value::Array(values) => values.iter().map(to_string).collect(),
+        value => vec![to_string(value)],
+    }
+}
+
+fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// The config files in merge order, each with whether it must exist
+fn layers(
+    file_name: &str,
+    user_dir: Option<PathBuf>,
+    config_argument: Option<PathBuf>
+) -> Vec<(PathBuf, bool)> {
+    let user_file = user_dir.map(|dir| (dir.join(file_name), false));
+    let project_file = Some((PathBuf::from(file_name), false));
+    let config_file = config_argument.map(|path| (path, true));
+    user_file.into_iter().chain(project_file).chain(config_file).collect()
+}
+
+fn load_layers(
+    layers: &[(PathBuf, bool)],
+    key: &impl Fn(&str) -> Option<Key>
+) -> Result<Table, String> {
+    let mut config = Table::new();
+    for (path, required) in layers {
+        let layer = read(path, *required)?;
+        check(&layer, "", key).map_err(|error| invalid(path, error))?;
+        merge(&mut config, layer);
+    }
+    Ok(config)
+}
+
+/// The path of `--config <path>` or `--config=<path>`
+fn config_argument(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--" {
+            break;
+        }
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+fn read(path: &Path, required: bool) -> Result<Table, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if !required && error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Table::new());
+        }
+        Err(error) => return Err(invalid(path, error)),
+    };
+    content.parse::<Table>().map_err(|error| invalid(path, error))
+}
+
+/// Checks that every key of `table` (below the dotted path `prefix`) is known to `key`
+fn check(table: &Table, prefix: &str, key: &impl Fn(&str) -> Option<Key>) -> Result<(), String> {
+    for (name, value) in table {
+        let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        match (key(&path), value) {
+            (Some(Key::Section), Value::Table(table)) => check(table, &path, key)?,
+            (Some(Key::Section), _) => {
+                return Err(format!("`{}` configures a subcommand and must be a table", path));
+            }
+            (Some(Key::Value), Value::Table(_)) => {
+                return Err(format!("`{}` configures an argument and must not be a table", path));
+            }
+            (Some(Key::Value), _) => {}
+            (None, _) => return Err(format!("unknown key `{}`", path)),
+        }
+    }
+    Ok(())
+}
+
+/// Merge `layer` into `base`, tables are merged key by key
+fn merge(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => merge(base_table, table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn invalid(path: &Path, error: impl Display) -> String {
+    format!("invalid config file {}: {}", path.display(), error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(path: &str) -> Option<Key> {
+        match path {
+            "verbose" | "region" | "deploy.region" | "deploy.replicas" => Some(Key::Value),
+            "deploy" => Some(Key::Section),
+            _ => None,
+        }
+    }
+
+    /// A directory with the given files, removed again on drop
+    struct Files(PathBuf);
+
+    impl Files {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!("tusks-config-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file, content) in files {
+                std::fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+                std::fs::write(dir.join(file), content).unwrap();
+            }
+            Files(dir)
+        }
+
+        fn path(&self, file: &str) -> PathBuf {
+            self.0.join(file)
+        }
+    }
+
+    impl Drop for Files {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_layers_order() {
+        assert_eq!(layers("tool.toml", Some(PathBuf::from("/home/user/.config")), None), [
+            (PathBuf::from("/home/user/.config/tool.toml"), false),
+            (PathBuf::from("tool.toml"), false),
+        ]);
+
+        assert_eq!(layers("tool.toml", None, Some(PathBuf::from("ci.toml"))), [
+            (PathBuf::from("tool.toml"), false),
+            (PathBuf::from("ci.toml"), true),
+        ]);
+    }
+
+    #[test]
+    fn test_later_layers_override_earlier_ones() {
+        let files = Files::new("merge", &[
+            ("user.toml", "verbose = true\nregion = \"user\"\n[deploy]\nregion = \"user\"\nreplicas = 1\n"),
+            ("project.toml", "region = \"project\"\n[deploy]\nregion = \"project\"\n"),
+            ("argument.toml", "[deploy]\nreplicas = 3\n"),
+        ]);
+
+        let config = load_layers(&[
+            (files.path("user.toml"), false),
+            (files.path("project.toml"), false),
+            (files.path("argument.toml"), true),
+        ], &key).unwrap();
+
+        let expected = "verbose = true\nregion = \"project\"\n[deploy]\nregion = \"project\"\nreplicas = 3\n";
+        assert_eq!(config, expected.parse::<Table>().unwrap());
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let files = Files::new("missing", &[]);
+        assert!(load_layers(&[(files.path("tool.toml"), false)], &key).unwrap().is_empty());
+
+        let error = load_layers(&[(files.path("tool.toml"), true)], &key).unwrap_err();
+        assert!(error.starts_with(&format!("invalid config file {}: ", files.path("tool.toml").display())));
+    }
+
+    #[test]
+    fn test_unknown_and_misplaced_keys() {
+        let check = |content: &str| check(&content.parse::<Table>().unwrap(), "", &key);
+
+        assert_eq!(check("verbose = false\n[deploy]\nreplicas = 2"), Ok(()));
+        assert_eq!(check("[deploy]\nzone = \"a\""), Err("unknown key `deploy.zone`".to_string()));
+        assert_eq!(check("[ext]\nregion = \"a\""), Err("unknown key `ext`".to_string()));
+        assert_eq!(
+            check("deploy = \"a\""),
+            Err("`deploy` configures a subcommand and must be a table".to_string())
+        );
+        assert_eq!(
+            check("[region]\nname = \"a\""),
+            Err("`region` configures an argument and must not be a table".to_string())
+        );
+    }
+
+    #[test]
+    fn test_config_argument() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(config_argument(&args(&["tool", "--config", "a.toml"])), Some(PathBuf::from("a.toml")));
+        assert_eq!(config_argument(&args(&["tool", "deploy", "--config=b.toml"])), Some(PathBuf::from("b.toml")));
+        assert_eq!(config_argument(&args(&["tool", "--", "--config", "a.toml"])), None);
+        assert_eq!(config_argument(&args(&["--config"])), None);
+    }
+
+    #[test]
+    fn test_default_values() {
+        let value = |content: &str| content.parse::<Table>().unwrap().remove("value").unwrap();
+
+        assert_eq!(default_values(&value("value = \"eu\"")), ["eu"]);
+        assert_eq!(default_values(&value("value = 3")), ["3"]);
+        assert_eq!(default_values(&value("value = [\"a\", true]")), ["a", "true"]);
+    }
+}
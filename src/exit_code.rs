@@ -0,0 +1,148 @@
+//! Runtime support for command functions returning `Result<T, E>`.
+//!
+//! The generated `handle_matches` functions use this module (via `::tusks::exit_code`) to report
+//! the error of a failed command on stderr and to map it to an exit code. The error type is
+//! concrete at every call site, so the most specific implementation is picked by autoref
+//! specialization:
+//! - if `E: std::error::Error`, the whole `source()` chain is printed, otherwise its `Display`
+//! - if `E: TuskExitCode`, its `exit_code()` is used, otherwise `1`
+
+use std::error::Error;
+use std::fmt::Display;
+
+/// Lets an error type returned by a command pick the exit code of the process.
+///
+/// # Example
+/// ```ignore
+/// impl tusks::exit_code::TuskExitCode for DeployError {
+///     fn exit_code(&self) -> u8 {
+///         match self {
+///             DeployError::NotFound => 2,
+///             DeployError::Forbidden => 3,
+///         }
+///     }
+/// }
+/// ```
+pub trait TuskExitCode {
+    /// The exit code for this error. `0` is replaced by `1`, as a failed command must not
+    /// report success.
+    fn exit_code(&self) -> u8;
+}
+
+/// Wraps the error of a command for the autoref specialization of the traits below
+pub struct Report<'a, E>(pub &'a E);
+
+/// Message for errors implementing `std::error::Error` (takes precedence)
+pub trait ErrorChainMessage {
+    fn tusks_message(&self) -> String;
+}
+
+impl<E: Error> ErrorChainMessage for &Report<'_, E> {
+    fn tusks_message(&self) -> String {
+        let mut message = self.0.to_string();
+        let mut source = self.0.source();
+
+        if source.is_some() {
+            message.push_str("\n\nCaused by:");
+        }
+
+        let mut index = 0;
+        while let Some(cause) = source {
+            message.push_str(&format!("\n    {}: {}", index, cause));
+            source = cause.source();
+            index += 1;
+        }
+
+        message
+    }
+}
+
+/// Message for errors only implementing `Display`
+pub trait DisplayMessage {
+    fn tusks_message(&self) -> String;
+}
+
+impl<E: Display> DisplayMessage for Report<'_, E> {
+    fn tusks_message(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Exit code for errors implementing `TuskExitCode` (takes precedence)
+pub trait CustomExitCode {
+    fn tusks_exit_code(&self) -> u8;
+}
+
+impl<E: TuskExitCode> CustomExitCode for &Report<'_, E> {
+    fn tusks_exit_code(&self) -> u8 {
+        self.0.exit_code().max(1)
+    }
+}
+
+/// Exit code for all other errors
+pub trait DefaultExitCode {
+    fn tusks_exit_code(&self) -> u8;
+}
+
+impl<E> DefaultExitCode for Report<'_, E> {
+    fn tusks_exit_code(&self) -> u8 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Inner;
+
+    impl Display for Inner {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "connection refused")
+        }
+    }
+
+    impl Error for Inner {}
+
+    #[derive(Debug)]
+    struct Outer(Inner);
+
+    impl Display for Outer {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "deploy failed")
+        }
+    }
+
+    impl Error for Outer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    impl TuskExitCode for Outer {
+        fn exit_code(&self) -> u8 {
+            7
+        }
+    }
+
+    #[test]
+    fn test_error_chain_and_custom_exit_code() {
+        let error = Outer(Inner);
+        let report = Report(&error);
+        assert_eq!(
+            (&&report).tusks_message(),
+            "deploy failed\n\nCaused by:\n    0: connection refused"
+        );
+        assert_eq!((&&report).tusks_exit_code(), 7);
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)] // mirrors the generated code
+    fn test_display_only_and_default_exit_code() {
+        let error = "invalid region".to_string();
+        let report = Report(&error);
+        assert_eq!((&&report).tusks_message(), "invalid region");
+        assert_eq!((&&report).tusks_exit_code(), 1);
+    }
+}
@@ -0,0 +1,395 @@
+//! The complete expansion of a `#[tusks(...)]` module in a single call, for the proc-macro
+//! itself as well as for build scripts, snapshot tests and custom wrapper macros.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Item, ItemMod, parse_quote};
+
+use crate::TusksModule;
+use crate::attribute::models::TusksAttr;
+use crate::completions::add_completions_function;
+use crate::manpages::add_manpages_function;
+use crate::dump::dump_expansion;
+use crate::fallback::{fallback_expansion, remove_tusks_attributes_from_module};
+use crate::file_modules::inline_file_modules;
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::parsing::validation::validate_root_functions;
+use crate::tasks::functions::{
+    add_complete_task_function,
+    add_execute_task_function,
+    add_show_help_for_task,
+    add_use_staements,
+    set_allow_external_subcommands,
+    validate_task_names
+};
+
+/// Expands a tusks module: `attr` are the arguments of the `#[tusks(...)]` attribute and `item`
+/// is the module it is attached to.
+///
+/// On errors the module is kept with its items (see `fallback_expansion`) together with a
+/// `compile_error!` for every error, so a tusks proc-macro can return the result as is.
+///
+/// # Example
+/// ```ignore
+/// #[proc_macro_attribute]
+/// pub fn tusks(attr: TokenStream, item: TokenStream) -> TokenStream {
+///     tusks_lib::expand(attr.into(), item.into()).into()
+/// }
+/// ```
+pub fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match try_expand(attr, item.clone()) {
+        Ok(expansion) => expansion,
+        Err(error) => match syn::parse2::<ItemMod>(item) {
+            Ok(module) => fallback_expansion(&module, error),
+            Err(_) => error.into_compile_error(),
+        },
+    }
+}
+
+/// Expands a tusks module like `expand`, but returns the errors instead of turning them into
+/// `compile_error!`s.
+///
+/// # Errors
+/// Returns all errors found in the module tree combined into one `syn::Error`. Iterating over
+/// it yields every single error with its span and message. If the expansion cannot be written
+/// to the file requested by `expand_to` or `TUSKS_EXPAND_DIR`, it holds a warning instead.
+pub fn try_expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let tusks_attr: TusksAttr = syn::parse2(attr)?;
+    let mut module: ItemMod = syn::parse2(item)?;
+
+    let files = inline_file_modules(&mut module)?;
+    expand_inlined(tusks_attr, module).map_err(|error| files.locate(error))
+}
+
+/// Expands a tusks module whose module files are inlined already
+pub(crate) fn expand_inlined(
+    tusks_attr: TusksAttr,
+    mut module: ItemMod
+) -> syn::Result<TokenStream> {
+    if let Some(ref tasks_config) = tusks_attr.tasks {
+        add_use_staements(&mut module);
+        set_allow_external_subcommands(&mut module);
+        add_execute_task_function(&mut module, tasks_config);
+        add_show_help_for_task(&mut module, tasks_config);
+        add_complete_task_function(&mut module, tasks_config);
+    }
+
+    // The script and the pages are generated from the Cli struct, which only the root has
+    if tusks_attr.completions {
+        require_root(&tusks_attr, &module, "completions")?;
+        add_completions_function(&mut module, tusks_attr.tasks.is_some());
+    }
+
+    if let Some(ref manpages_config) = tusks_attr.manpages {
+        require_root(&tusks_attr, &module, "manpages")?;
+        if manpages_config.command {
+            add_manpages_function(&mut module);
+        }
+    }
+
+    let Some(mut tusks_module) = TusksModule::from_module(
+        module.clone(),
+        tusks_attr.root,
+        0,
+        tusks_attr.explicit.unwrap_or(false),
+        tusks_attr.rename_all
+    )? else {
+        return Err(syn::Error::new_spanned(
+            &module.ident,
+            "tusks module must be public (pub, pub(crate), ...) and must not be marked with #[skip]"
+        ));
+    };
+
+    let mut diagnostics = Diagnostics::new();
+
+    tusks_module.inherit_env_prefix(tusks_attr.env_prefix.as_deref());
+    if tusks_attr.root {
+        tusks_module.config_file = tusks_attr.config_file.clone();
+        diagnostics.check(tusks_module.validate_config_option());
+        diagnostics.check(validate_root_functions(&module, tusks_attr.manpages.is_some()));
+    }
+
+    if let Some(ref tasks_config) = tusks_attr.tasks {
+        diagnostics.check(validate_task_names(&tusks_module, tasks_config));
+    }
+
+    diagnostics.check(tusks_module.supplement_parameters(
+        &mut module,
+        tusks_attr.root,
+        tusks_attr.derive_debug_for_parameters
+    ));
+
+    diagnostics.finish()?;
+
+    let cli = tusks_module.build_cli(Vec::new(), tusks_attr.debug);
+    let config = tusks_module.build_config();
+    let manpages = if tusks_attr.manpages.is_some() {
+        tusks_module.build_manpages()
+    } else {
+        quote! {}
+    };
+    let handle_matches = tusks_module.build_handle_matches(
+        tusks_attr.root,
+        tusks_attr.async_runtime.as_ref()
+    );
+
+    remove_tusks_attributes_from_module(&mut module);
+
+    let mut generated_items: Vec<Item> = vec![parse_quote! {
+        // Not every command uses the parameters of its module. The lint only shows up when the
+        // code is compiled outside of the macro, e.g. from a build script.
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        pub mod __internal_tusks_module {
+            pub mod cli {
+                #cli
+            }
+
+            #handle_matches
+
+            #config
+
+            #manpages
+        }
+    }];
+
+    if tusks_attr.root {
+        let parse_cli = if tusks_module.config_file.is_some() {
+            quote! { __internal_tusks_module::config::parse_cli() }
+        } else {
+            quote! {{
+                use ::tusks::clap::Parser as _;
+                __internal_tusks_module::cli::Cli::parse()
+            }}
+        };
+
+        generated_items.push(parse_quote! {
+            /// Parse the command line arguments and run the selected command.
+            /// Returns the exit code of the command, if it has one.
+            pub fn exec_cli() -> Option<u8> {
+                let cli = #parse_cli;
+                __internal_tusks_module::handle_matches(&cli)
+            }
+        });
+    }
+
+    if tusks_attr.manpages.is_some() {
+        generated_items.push(parse_quote! {
+            /// Write the man pages of all commands to `dir`, which is created if missing.
+            /// Returns the written files.
+            pub fn write_manpages(
+                dir: impl AsRef<::std::path::Path>
+            ) -> ::std::io::Result<Vec<::std::path::PathBuf>> {
+                __internal_tusks_module::manpages::write_all(dir.as_ref())
+            }
+        });
+    }
+
+    if let Some((_, ref mut items)) = module.content {
+        items.extend(generated_items);
+    }
+
+    let expansion = quote! { #module };
+    let dump_warning = dump_expansion(&tusks_attr, &module.ident, &expansion);
+
+    Ok(quote! {
+        #expansion
+        #dump_warning
+    })
+}
+
+/// Checks that `option` of `#[tusks(...)]` is set on the root module
+fn require_root(tusks_attr: &TusksAttr, module: &ItemMod, option: &str) -> syn::Result<()> {
+    if tusks_attr.root {
+        return Ok(());
+    }
+    Err(syn::Error::new_spanned(
+        &module.ident,
+        format!("`{}` requires `root`, it covers the whole command tree", option)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::dump::pretty_print;
+
+    /// Compares the pretty-printed expansion with `snapshots/<name>.rs`.
+    /// `TUSKS_UPDATE_SNAPSHOTS=1` writes the snapshot instead.
+    fn assert_snapshot(name: &str, attr: TokenStream, item: TokenStream) {
+        let expansion = pretty_print(&try_expand(attr, item).unwrap());
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(format!("{}.rs", name));
+
+        if std::env::var_os("TUSKS_UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &expansion).unwrap();
+            return;
+        }
+
+        let snapshot = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            snapshot == expansion,
+            "expansion differs from {}, rerun with TUSKS_UPDATE_SNAPSHOTS=1 to update it:\n{}",
+            path.display(),
+            expansion
+        );
+    }
+
+    #[test]
+    fn test_expand_root() {
+        assert_snapshot("root", quote! { root }, quote! {
+            #[command(name = "tool", version = "1.0")]
+            pub mod cli {
+                pub struct Parameters<'a> {
+                    #[arg(long)]
+                    pub verbose: &'a bool,
+                }
+
+                /// Deploy the application
+                pub fn deploy(params: &Parameters, #[arg(long)] region: Option<&str>) -> u8 {
+                    0
+                }
+
+                #[default]
+                pub fn status() {}
+            }
+        });
+    }
+
+    #[test]
+    fn test_expand_submodule() {
+        assert_snapshot("submodule", quote! { root, rename_all = "snake_case" }, quote! {
+            pub mod cli {
+                pub struct Parameters<'a> {
+                    #[arg(long)]
+                    pub verbose: &'a bool,
+                }
+
+                pub mod db {
+                    pub struct Parameters<'a> {
+                        #[arg(long)]
+                        pub url: &'a String,
+                    }
+
+                    pub fn run_migrations(params: &Parameters) -> Result<(), String> {
+                        Ok(())
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_expand_tasks() {
+        let attr = quote! { root, tasks(max_groupsize = 5, separator = ".") };
+        assert_snapshot("tasks", attr, quote! {
+            pub mod tasks {
+                pub fn build() {}
+
+                pub mod db {
+                    pub fn migrate() {}
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_expand_external_module() {
+        assert_snapshot("external_module", quote! { root }, quote! {
+            pub mod cli {
+                pub fn status() {}
+
+                /// Manage the cloud resources
+                pub use crate::cloud;
+            }
+        });
+
+        assert_snapshot("external_child", quote! {}, quote! {
+            pub mod cloud {
+                pub use crate::cli as parent_;
+
+                pub fn upload() {}
+            }
+        });
+    }
+
+    #[test]
+    fn test_expand_cfg_command() {
+        assert_snapshot("cfg_command", quote! { root }, quote! {
+            pub mod cli {
+                pub fn status() {}
+
+                #[cfg(feature = "cloud")]
+                pub fn upload(#[arg(long)] bucket: String) {}
+            }
+        });
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let item = quote! {
+            pub mod cli {
+                #[default]
+                pub fn status() -> String { String::new() }
+                pub fn deploy() {}
+                #[command(name = "deploy")]
+                pub fn other() {}
+            }
+        };
+
+        let messages: Vec<String> = try_expand(quote! { root }, item.clone()).unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].starts_with("command function must return"));
+        assert!(messages[1].starts_with("function `other` collides with function `deploy`"));
+
+        // The module is kept without the tusks attributes, followed by the errors
+        let fallback = pretty_print(&expand(quote! { root }, item));
+        assert!(fallback.starts_with("pub mod cli {\n    pub fn status() -> String"));
+        assert!(!fallback.contains("#[default]"));
+        assert!(!fallback.contains("__internal_tusks_module"));
+        assert_eq!(fallback.matches("::core::compile_error!").count(), 3);
+    }
+
+    #[test]
+    fn test_expand_with_failing_dump() {
+        let item = quote! { pub mod cli { pub fn status() {} } };
+        let expansion = try_expand(quote! { root, expand_to = "/dev/null/cli.rs" }, item).unwrap();
+
+        let expansion = pretty_print(&expansion);
+        assert!(expansion.contains("pub fn exec_cli()"));
+        assert!(expansion.contains("failed to write the expansion to /dev/null/cli.rs"));
+    }
+
+    #[test]
+    fn test_expand_invalid_input() {
+        // Without a module to fall back to, only the errors remain
+        let expansion = expand(quote! { root }, quote! { pub fn cli() {} }).to_string();
+        assert!(expansion.starts_with(":: core :: compile_error !"));
+
+        let expansion = expand(quote! { rooot }, quote! { pub mod cli {} }).to_string();
+        assert!(expansion.contains("unknown tusks attribute: rooot"));
+        assert!(expansion.starts_with("pub mod cli {"));
+
+        let error = try_expand(quote! {}, quote! { mod cli { pub fn run() {} } }).unwrap_err();
+        assert!(error.to_string().starts_with("tusks module must be public"));
+    }
+
+    // Without the feature, the attribute fails already while parsing
+    #[cfg(feature = "completions")]
+    #[test]
+    fn test_expand_completions_requires_root() {
+        let item = quote! { pub mod db { pub fn migrate() {} } };
+        let error = try_expand(quote! { completions }, item).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`completions` requires `root`, it covers the whole command tree"
+        );
+    }
+}
@@ -1,9 +1,47 @@
+//! Parsing and code generation of the `#[tusks]` attribute macro.
+//!
+//! The generated code refers to the runtime crate as `::tusks`, which re-exports `clap`,
+//! `tusks_lib::exit_code` and `tusks_lib::task_names`. The crates used by optional options are re-exported behind features,
+//! which the runtime crate enables for both the macro and itself:
+//! - `config` re-exports `toml` and `tusks_lib::config` for `config_file`
+//! - `completions` re-exports `clap_complete` for `completions`
+//! - `manpages` re-exports `clap_mangen` and `tusks_lib::man_roff` for `manpages`
+//! - `async` re-exports `tokio` and `futures` for `async_runtime = "tokio"` and
+//!   `async_runtime = "futures"`
+//!
+//! Using such an option while its feature is disabled is a compile error naming the feature.
+
 mod models;
 mod parsing;
 mod codegen;
+mod expand;
+pub mod exit_code;
+pub mod task_names;
+pub mod build;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "manpages")]
+pub mod man_roff;
 
 pub use models::TusksModule;
 pub use parsing::util::attr::AttributeCheck;
 pub use parsing::util::get_attribute_value::AttributeValue;
 pub use parsing::attribute;
 pub use codegen::preparse::tasks;
+pub use codegen::preparse::file_modules;
+pub use codegen::preparse::completions;
+pub use codegen::preparse::manpages;
+pub use codegen::fallback;
+pub use codegen::dump;
+pub use expand::{expand, try_expand};
+
+#[cfg(feature = "config")]
+pub use toml;
+#[cfg(feature = "completions")]
+pub use clap_complete;
+#[cfg(feature = "manpages")]
+pub use clap_mangen;
+#[cfg(feature = "async")]
+pub use tokio;
+#[cfg(feature = "async")]
+pub use futures;
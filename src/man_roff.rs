@@ -0,0 +1,44 @@
+//! Runtime support for `#[tusks(manpages)]`.
+//!
+//! The generated `manpages::write_all` uses this module (via `::tusks::man_roff`) to edit the
+//! roff pages rendered by `clap_mangen`.
+
+/// Replaces the SYNOPSIS section of `page` with the one rendered in `synopsis`
+pub fn replace_synopsis(page: &str, synopsis: &str) -> String {
+    const HEADER: &str = ".SH SYNOPSIS";
+    let (Some(start), Some(own)) = (page.find(HEADER), synopsis.find(HEADER)) else {
+        return page.to_string();
+    };
+    let end = page[start..].find("\n.SH ").map_or(page.len(), |end| start + end + 1);
+    format!("{}{}{}", &page[..start], &synopsis[own..], &page[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_synopsis() {
+        let page = ".TH tool-deploy 1\n.SH NAME\ntool\\-deploy\n.SH SYNOPSIS\n\\fBtool deploy\\fR [\\fB\\-\\-verbose\\fR]\n.SH DESCRIPTION\nDeploy\n";
+        let synopsis = ".SH SYNOPSIS\n[\\fB\\-\\-verbose\\fR] \\fBtool deploy\\fR\n";
+
+        assert_eq!(
+            replace_synopsis(page, synopsis),
+            ".TH tool-deploy 1\n.SH NAME\ntool\\-deploy\n.SH SYNOPSIS\n[\\fB\\-\\-verbose\\fR] \\fBtool deploy\\fR\n.SH DESCRIPTION\nDeploy\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_synopsis_as_last_section() {
+        let page = ".SH NAME\ntool\n.SH SYNOPSIS\n\\fBtool\\fR\n";
+        let synopsis = ".SH SYNOPSIS\n\\fBtool\\fR [\\fIOPTIONS\\fR]\n";
+        assert_eq!(replace_synopsis(page, synopsis), ".SH NAME\ntool\n.SH SYNOPSIS\n\\fBtool\\fR [\\fIOPTIONS\\fR]\n");
+    }
+
+    #[test]
+    fn test_replace_synopsis_without_section() {
+        let page = ".SH NAME\ntool\n";
+        assert_eq!(replace_synopsis(page, ".SH SYNOPSIS\n\\fBtool\\fR\n"), page);
+        assert_eq!(replace_synopsis(".SH SYNOPSIS\nold\n", "no synopsis"), ".SH SYNOPSIS\nold\n");
+    }
+}
@@ -1,4 +1,7 @@
-use syn::{Attribute, Ident, ItemFn, ItemStruct, PathSegment, spanned::Spanned};
+use proc_macro2::Span;
+use syn::{Attribute, Ident, ItemFn, ItemStruct, Path, PathSegment, Type, spanned::Spanned};
+
+use crate::attribute::models::RenameRule;
 
 #[derive(Default)]
 pub struct Attributes(pub Vec<Attribute>);
@@ -28,12 +31,63 @@ pub struct TusksModule {
 
     /// if #[command(allow_external_subcommands=true)] is set
     pub allow_external_subcommands: bool,
+
+    /// Naming convention of the commands in this module, set via #[tusks(rename_all = "...")]
+    /// or inherited from the parent module
+    pub rename_all: Option<RenameRule>,
+
+    /// If only items marked with #[command] or #[tusk] become commands, set via
+    /// #[tusks(explicit)] or inherited from the parent module
+    pub explicit: bool,
+
+    /// Prefix of the environment variables of the Parameters fields, e.g. `MYTOOL_DEPLOY`,
+    /// derived from #[tusks(env_prefix = "...")] of the root and the module path
+    pub env_prefix: Option<String>,
+
+    /// Name of the config file set via #[tusks(config_file = "...")], only on the root module
+    pub config_file: Option<String>,
+
+    /// Warnings found while parsing, emitted together with the generated cli code
+    pub warnings: Vec<(Span, String)>,
+
+    /// The std types the module imports, which the bare names of argument types refer to
+    pub std_imports: StdImports,
+}
+
+/// The borrowed std types a module imports under their own name, e.g. via
+/// `use std::path::Path;`. A bare `Path` or `OsStr` only maps to its owned type if imported.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StdImports {
+    /// `std::path::Path`
+    pub path: bool,
+    /// `std::ffi::OsStr`
+    pub os_str: bool,
 }
 
 /// Represents a parameters struct
 pub struct TusksParameters {
     /// The underlying struct
     pub pstruct: ItemStruct,
+
+    /// Conversions of owned fields declared via #[tusks(from = ...)] or #[tusks(try_from = ...)]
+    pub conversions: Vec<FieldConversion>,
+}
+
+/// Represents a conversion of a parameters field, e.g.
+/// `#[tusks(from = load_config, arg_type = PathBuf)] pub config: Config`
+pub struct FieldConversion {
+    /// The field the converted value is assigned to
+    pub field: Ident,
+
+    /// Function converting a reference to the raw argument into the field value
+    pub from: Path,
+
+    /// Declared via `try_from`: the function returns a `Result` whose error is reported
+    /// like an invalid argument
+    pub fallible: bool,
+
+    /// The type of the raw argument (String if not specified)
+    pub arg_type: Type,
 }
 
 /// Represents a command function (tusk)
@@ -2,7 +2,22 @@ pub struct TusksAttr {
     pub debug: bool,
     pub root: bool,
     pub derive_debug_for_parameters: bool,
+    /// Adds the `completions <shell>` command printing the shell completion scripts
+    pub completions: bool,
+    /// Generates `write_manpages` writing the man pages of the whole command tree
+    pub manpages: Option<ManpagesConfig>,
     pub tasks: Option<TasksConfig>,
+    pub async_runtime: Option<AsyncRuntime>,
+    pub rename_all: Option<RenameRule>,
+    /// Only items marked with `#[command]` or `#[tusk]` become commands.
+    /// `None` if not set, then the setting of the parent module applies.
+    pub explicit: Option<bool>,
+    /// Prefix of the environment variables generated for all Parameters fields
+    pub env_prefix: Option<String>,
+    /// Name of the config files holding defaults for the Parameters fields
+    pub config_file: Option<String>,
+    /// File the pretty-printed expansion is written to, relative to the crate root
+    pub expand_to: Option<syn::LitStr>,
 }
 
 pub struct TasksConfig {
@@ -12,6 +27,43 @@ pub struct TasksConfig {
     pub use_colors: bool,
 }
 
+#[derive(Default)]
+pub struct ManpagesConfig {
+    /// Adds the hidden `manpages <dir>` command calling `write_manpages`
+    pub command: bool,
+}
+
+/// The executor driving `async fn` commands
+pub enum AsyncRuntime {
+    /// A multi-threaded `tokio` runtime
+    Tokio,
+    /// `futures::executor::block_on`
+    Futures,
+    /// Path to a function creating the executor, which must provide `block_on(&self, future)`
+    Custom(syn::Path),
+}
+
+/// Naming convention for the generated command names, as in `#[tusks(rename_all = "...")]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+    /// `kebab-case`, which is also the convention of clap if no rule is set
+    KebabCase,
+    /// `snake_case`
+    SnakeCase,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnakeCase,
+    /// `camelCase`
+    CamelCase,
+    /// `PascalCase`
+    PascalCase,
+    /// `lower`
+    Lower,
+    /// `UPPER`
+    Upper,
+    /// `verbatim`, the function or module name as written
+    Verbatim,
+}
+
 impl Default for TasksConfig {
     fn default() -> Self {
         Self {
@@ -29,7 +81,15 @@ impl Default for TusksAttr {
             debug: false,
             root: false,
             derive_debug_for_parameters: false,
+            completions: false,
+            manpages: None,
             tasks: None,
+            async_runtime: None,
+            rename_all: None,
+            explicit: None,
+            env_prefix: None,
+            config_file: None,
+            expand_to: None,
         }
     }
 }
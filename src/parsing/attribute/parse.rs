@@ -1,24 +1,43 @@
 use syn::{Ident, LitBool, LitInt, LitStr, Token, parenthesized, parse::{Parse, ParseStream}};
 
-use crate::parsing::attribute::models::{TasksConfig, TusksAttr};
+use crate::parsing::attribute::models::{
+    AsyncRuntime, ManpagesConfig, RenameRule, TasksConfig, TusksAttr
+};
 
 impl Parse for TusksAttr {
     /// Parses the `#[tusks(...)]` attribute and extracts all configuration options.
     /// 
     /// Supports the following syntax:
-    /// - Boolean flags: `debug`, `root`, `derive_debug_for_parameters`
+    /// - Boolean flags: `debug`, `root`, `derive_debug_for_parameters`, `explicit`, `completions`
     ///   - Can be specified as just the flag name (implies `true`)
     ///   - Or with explicit value: `debug = true` or `debug = false`
     /// - Nested configuration: `tasks(max_groupsize=5, max_depth=20, separator=".")`
+    /// - Executor for `async fn` commands: `async_runtime = "tokio"`, `async_runtime = "futures"`
+    ///   (both require the `async` feature) or `async_runtime = path::to::executor_constructor`
+    /// - Naming convention of the commands: `rename_all = "kebab-case"`, `"snake_case"`,
+    ///   `"SCREAMING_SNAKE_CASE"`, `"camelCase"`, `"PascalCase"`, `"lower"`, `"UPPER"`
+    ///   or `"verbatim"`
+    /// - Environment variables for all Parameters fields: `env_prefix = "MYTOOL"` gives e.g.
+    ///   `MYTOOL_DEPLOY_REGION` for the field `region` of the submodule `deploy`
+    ///   (requires the `env` feature of clap)
+    /// - Defaults for the Parameters fields from TOML files: `config_file = "mytool.toml"`,
+    ///   adds a `--config <path>` option (requires the `config` feature)
+    /// - `completions` adds a `completions <shell>` command to the root module
+    ///   (requires the `completions` feature)
+    /// - Man pages of the command tree: `manpages` generates `write_manpages(dir)` in the root
+    ///   module, `manpages(command)` adds a hidden `manpages <dir>` command as well
+    ///   (requires the `manpages` feature)
+    /// - File to write the pretty-printed expansion to: `expand_to = "target/tusks/cli.rs"`
     /// 
     /// # Example
     /// ```ignore
-    /// #[tusks(root, debug, tasks(max_groupsize=10, separator="/"))]
+    /// #[tusks(root, debug, tasks(max_groupsize=10, separator="/"), async_runtime = "tokio")]
     /// ```
     /// 
     /// # Errors
     /// Returns an error if:
     /// - An unknown attribute name is encountered
+    /// - An option is used whose cargo feature is disabled
     /// - The syntax is malformed (missing commas, invalid values, etc.)
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut attr = TusksAttr::default();
@@ -32,9 +51,36 @@ impl Parse for TusksAttr {
                 "derive_debug_for_parameters" => {
                     attr.derive_debug_for_parameters = parse_bool_flag(input)?
                 },
+                "completions" => {
+                    attr.completions = parse_bool_flag(input)?;
+                    if attr.completions {
+                        require_feature(&ident, "completions", cfg!(feature = "completions"))?;
+                    }
+                },
+                "manpages" => {
+                    attr.manpages = Some(parse_optional_nested_config::<ManpagesConfig>(input)?);
+                    require_feature(&ident, "manpages", cfg!(feature = "manpages"))?;
+                },
                 "tasks" => {
                     attr.tasks = Some(parse_optional_nested_config::<TasksConfig>(input)?);
                 },
+                "async_runtime" => {
+                    let async_runtime = parse_required_value(input, parse_async_runtime)?;
+                    if matches!(async_runtime, AsyncRuntime::Tokio | AsyncRuntime::Futures) {
+                        require_feature(&ident, "async", cfg!(feature = "async"))?;
+                    }
+                    attr.async_runtime = Some(async_runtime);
+                },
+                "rename_all" => {
+                    attr.rename_all = Some(parse_required_value(input, parse_rename_rule)?);
+                },
+                "env_prefix" => attr.env_prefix = Some(parse_required_value(input, parse_string)?),
+                "config_file" => {
+                    attr.config_file = Some(parse_required_value(input, parse_string)?);
+                    require_feature(&ident, "config", cfg!(feature = "config"))?;
+                },
+                "explicit" => attr.explicit = Some(parse_bool_flag(input)?),
+                "expand_to" => attr.expand_to = Some(parse_required_value(input, |input| input.parse())?),
                 other => return Err(unknown_attribute_error(&ident, other)),
             }
             
@@ -89,7 +135,7 @@ impl Parse for TasksConfig {
                 "max_depth" => config.max_depth = parse_required_value(input, parse_usize)?,
                 "separator" => config.separator = parse_required_value(input, parse_string)?,
                 "use_colors" => config.use_colors = parse_bool_flag(input)?,
-                other => return Err(unknown_parameter_error(&ident, other)),
+                other => return Err(unknown_parameter_error("tasks", &ident, other)),
             }
             
             parse_trailing_comma(input)?;
@@ -99,6 +145,30 @@ impl Parse for TasksConfig {
     }
 }
 
+impl Parse for ManpagesConfig {
+    /// Parses the man page configuration inside `manpages(...)`:
+    /// - `command`: adds the hidden `manpages <dir>` command, defaults to false
+    ///
+    /// # Errors
+    /// Returns an error for unknown parameters or a malformed flag
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut config = ManpagesConfig::default();
+
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+
+            match ident.to_string().as_str() {
+                "command" => config.command = parse_bool_flag(input)?,
+                other => return Err(unknown_parameter_error("manpages", &ident, other)),
+            }
+
+            parse_trailing_comma(input)?;
+        }
+
+        Ok(config)
+    }
+}
+
 // Helper functions
 
 /// Parse an optional boolean flag that can be either `flag` or `flag = true/false`
@@ -148,6 +218,51 @@ fn parse_string(input: ParseStream) -> syn::Result<String> {
     Ok(value.value())
 }
 
+/// Parse the executor for async commands: `"tokio"`, `"futures"`, or a path
+/// (either bare or as a string literal)
+fn parse_async_runtime(input: ParseStream) -> syn::Result<AsyncRuntime> {
+    if !input.peek(LitStr) {
+        return Ok(AsyncRuntime::Custom(input.parse()?));
+    }
+
+    let value: LitStr = input.parse()?;
+    match value.value().as_str() {
+        "tokio" => Ok(AsyncRuntime::Tokio),
+        "futures" => Ok(AsyncRuntime::Futures),
+        _ => Ok(AsyncRuntime::Custom(value.parse().map_err(|_| {
+            syn::Error::new(
+                value.span(),
+                "async_runtime must be \"tokio\", \"futures\" or a path to a function \
+                    creating the executor"
+            )
+        })?)),
+    }
+}
+
+/// Parse a naming convention like `"kebab-case"`, using the same names as clap and serde
+fn parse_rename_rule(input: ParseStream) -> syn::Result<RenameRule> {
+    let value: LitStr = input.parse()?;
+    match value.value().as_str() {
+        "kebab-case" => Ok(RenameRule::KebabCase),
+        "snake_case" => Ok(RenameRule::SnakeCase),
+        "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+        "camelCase" => Ok(RenameRule::CamelCase),
+        "PascalCase" => Ok(RenameRule::PascalCase),
+        "lower" | "lowercase" => Ok(RenameRule::Lower),
+        "UPPER" | "UPPERCASE" => Ok(RenameRule::Upper),
+        "verbatim" => Ok(RenameRule::Verbatim),
+        other => Err(syn::Error::new(
+            value.span(),
+            format!(
+                "unknown rename_all rule: {}, expected one of \"kebab-case\", \"snake_case\", \
+                    \"SCREAMING_SNAKE_CASE\", \"camelCase\", \"PascalCase\", \"lower\", \
+                    \"UPPER\", \"verbatim\"",
+                other
+            )
+        )),
+    }
+}
+
 /// Create error for unknown attribute
 fn unknown_attribute_error(ident: &Ident, name: &str) -> syn::Error {
     syn::Error::new(
@@ -156,10 +271,72 @@ fn unknown_attribute_error(ident: &Ident, name: &str) -> syn::Error {
     )
 }
 
-/// Create error for unknown parameter
-fn unknown_parameter_error(ident: &Ident, name: &str) -> syn::Error {
+/// Fail for an option whose runtime crate is only re-exported with the cargo feature `feature`
+fn require_feature(ident: &Ident, feature: &str, enabled: bool) -> syn::Result<()> {
+    if enabled {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        ident.span(),
+        format!("tusks attribute {} requires the `{}` feature of tusks", ident, feature)
+    ))
+}
+
+/// Create error for unknown parameter of a nested configuration like `tasks(...)`
+fn unknown_parameter_error(config: &str, ident: &Ident, name: &str) -> syn::Error {
     syn::Error::new(
         ident.span(),
-        format!("unknown tasks parameter: {}", name)
+        format!("unknown {} parameter: {}", config, name)
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn parse_runtime(tokens: proc_macro2::TokenStream) -> syn::Result<AsyncRuntime> {
+        let attr: TusksAttr = syn::parse2(tokens)?;
+        Ok(attr.async_runtime.expect("async_runtime is set"))
+    }
+
+    #[test]
+    fn test_parse_async_runtime() {
+        if cfg!(feature = "async") {
+            assert!(matches!(parse_runtime(quote! { async_runtime = "tokio" }), Ok(AsyncRuntime::Tokio)));
+            assert!(matches!(
+                parse_runtime(quote! { root, async_runtime = "futures" }),
+                Ok(AsyncRuntime::Futures)
+            ));
+        } else {
+            let Err(error) = parse_runtime(quote! { async_runtime = "tokio" }) else {
+                panic!("expected an error");
+            };
+            assert_eq!(
+                error.to_string(),
+                "tusks attribute async_runtime requires the `async` feature of tusks"
+            );
+        }
+
+        let paths = [
+            quote! { async_runtime = crate::rt::build },
+            quote! { async_runtime = "crate::rt::build" },
+        ];
+        for tokens in paths {
+            let Ok(AsyncRuntime::Custom(path)) = parse_runtime(tokens) else {
+                panic!("expected a custom runtime");
+            };
+            assert_eq!(quote! { #path }.to_string(), "crate :: rt :: build");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_async_runtime() {
+        let Err(error) = parse_runtime(quote! { async_runtime = "tokio runtime" }) else {
+            panic!("expected an error");
+        };
+        assert!(error.to_string().starts_with("async_runtime must be \"tokio\", \"futures\""));
+
+        assert!(parse_runtime(quote! { async_runtime }).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 mod module;
 mod parameters;
+mod std_imports;
 mod tusk;
+pub(crate) mod validation;
 pub mod attribute;
 pub mod util;
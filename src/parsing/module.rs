@@ -1,12 +1,45 @@
-use crate::{models::{Attributes, ExternalModule, Tusk, TusksModule, TusksParameters}, parsing::util::get_attribute_value::AttributeValue};
+use crate::{models::{Attributes, ExternalModule, StdImports, Tusk, TusksModule, TusksParameters}, parsing::util::get_attribute_value::AttributeValue};
 use syn::spanned::Spanned;
 use crate::parsing::util::attr::AttributeCheck;
 
-use syn::{ItemMod, ItemStruct};
+use crate::attribute::models::{RenameRule, TusksAttr};
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::parsing::util::visibility::{check_reachable_visibility, is_eligible_visibility};
+use crate::codegen::util::enum_util::ident_name;
+
+use syn::{Attribute, Ident, ItemMod, ItemStruct};
 
 impl TusksModule {
     /// Parses a syn::ItemMod into a TusksModule
-    pub fn from_module(module: ItemMod, is_tusks_root: bool, is_root: bool) -> syn::Result<Option<Self>> {
+    ///
+    /// Invalid items do not stop the parsing, the errors of all items in the module tree are
+    /// returned combined.
+    ///
+    /// `depth` is the number of modules between this module and the one the `#[tusks]` attribute
+    /// is attached to, 0 for that module itself.
+    ///
+    /// `explicit` and `rename_all` are the settings of the parent module (or of the
+    /// `#[tusks(...)]` attribute for the root), which apply unless the module sets its own via
+    /// `#[tusks(explicit)]` or `#[tusks(rename_all = "...")]`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[tusks(root, rename_all = "snake_case")]
+    /// pub mod cli {
+    ///     pub mod db { pub fn run_migrations() {} }        // run_migrations
+    ///
+    ///     #[tusks(rename_all = "camelCase")]
+    ///     pub mod cloud { pub fn list_buckets() {} }       // listBuckets
+    /// }
+    /// ```
+    pub fn from_module(
+        module: ItemMod,
+        is_tusks_root: bool,
+        depth: usize,
+        explicit: bool,
+        rename_all: Option<RenameRule>
+    ) -> syn::Result<Option<Self>> {
+        let is_root = depth == 0;
         let allow_external_subcommands = module.get_attribute_bool(
             "command",
             "allow_external_subcommands"
@@ -16,9 +49,12 @@ impl TusksModule {
         let span = module.span();
 
         // Validate that the module is public
-        if !matches!(module.vis, syn::Visibility::Public(_)) {
+        if !is_eligible_visibility(&module.vis) {
             if module.has_attr("tusks") {
-                return Err(syn::Error::new_spanned(&name, "tusks module must be public"));
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    "tusks module must be public (pub, pub(crate), pub(super), ...)"
+                ));
             }
             return Ok(None);
         }
@@ -38,6 +74,11 @@ impl TusksModule {
             }
         };
 
+        let mut diagnostics = Diagnostics::new();
+        let module_attr = diagnostics.check(Self::parse_module_attr(&module.attrs)).flatten();
+        let rename_all = module_attr.as_ref().and_then(|attr| attr.rename_all).or(rename_all);
+        let explicit = module_attr.and_then(|attr| attr.explicit).unwrap_or(explicit);
+
         let mut tusks_module = TusksModule {
             name,
             attrs: Attributes(module.attrs),
@@ -47,13 +88,86 @@ impl TusksModule {
             submodules: Vec::new(),
             external_modules: Vec::new(),
             allow_external_subcommands,
+            rename_all,
+            explicit,
+            env_prefix: None,
+            config_file: None,
+            warnings: Vec::new(),
+            std_imports: StdImports::from_items(&items),
         };
         
-        tusks_module.extract_module_items(items, is_root)?;
+        tusks_module.extract_module_items(items, depth, &mut diagnostics);
+
+        // The names of the whole tree are validated once, from the root
+        if is_root {
+            diagnostics.check(tusks_module.validate_names());
+        }
 
-        tusks_module.validate_is_root_or_has_parent(is_tusks_root, is_root)?;
+        diagnostics.check(tusks_module.validate_is_root_or_has_parent(is_tusks_root, is_root));
         
-        Ok(Some(tusks_module))
+        diagnostics.finish_with(Some(tusks_module))
+    }
+
+    /// Read the settings of a submodule from its `#[tusks(rename_all = "...", explicit)]` attribute
+    fn parse_module_attr(attrs: &[Attribute]) -> syn::Result<Option<TusksAttr>> {
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("tusks")) {
+            if let syn::Meta::List(_) = attr.meta {
+                let tusks_attr: TusksAttr = attr.parse_args()?;
+                if tusks_attr.rename_all.is_some() || tusks_attr.explicit.is_some() {
+                    return Ok(Some(tusks_attr));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks if an attribute is a `#[tusks(...)]` attribute setting `rename_all` or `explicit`
+    pub fn is_module_config_attribute(attr: &Attribute) -> bool {
+        attr.path().is_ident("tusks")
+            && attr.parse_args::<TusksAttr>().is_ok_and(|tusks_attr| {
+                tusks_attr.rename_all.is_some() || tusks_attr.explicit.is_some()
+            })
+    }
+
+    /// Set the prefix of the environment variables of the Parameters fields: `env_prefix` for
+    /// this module, extended by the module name for each submodule.
+    /// Must be called with the `env_prefix` of the `#[tusks(...)]` attribute of the root module.
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[tusks(root, env_prefix = "MYTOOL")]
+    /// pub mod cli {
+    ///     pub struct Parameters<'a> { pub verbose: &'a bool }     // MYTOOL_VERBOSE
+    ///
+    ///     pub mod deploy {
+    ///         pub struct Parameters<'a> { pub region: &'a String } // MYTOOL_DEPLOY_REGION
+    ///     }
+    /// }
+    /// ```
+    pub fn inherit_env_prefix(&mut self, env_prefix: Option<&str>) {
+        self.env_prefix = env_prefix.map(str::to_string);
+
+        for submodule in &mut self.submodules {
+            let submodule_prefix = env_prefix.map(|prefix| format!(
+                "{}_{}",
+                prefix,
+                RenameRule::ScreamingSnakeCase.apply(&ident_name(&submodule.name))
+            ));
+            submodule.inherit_env_prefix(submodule_prefix.as_deref());
+        }
+    }
+
+    /// Checks if the module has any commands (tusks, submodules, or external modules).
+    /// Only then a `Commands` enum and a `sub` field are generated for it, so a module
+    /// containing nothing but submodules works as a pure namespace.
+    ///
+    /// # Example
+    /// Input: `pub mod cloud { pub mod aws { .. } pub mod gcp { .. } }`
+    /// Output: true
+    pub fn has_commands(&self) -> bool {
+        !self.tusks.is_empty() ||
+        !self.submodules.is_empty() ||
+        !self.external_modules.is_empty()
     }
 
     fn validate_is_root_or_has_parent(&self, is_tusks_root: bool, is_root: bool) -> syn::Result<()> {
@@ -84,37 +198,94 @@ impl TusksModule {
         return Ok(());
     }
     
-    /// Extract all relevant items from a module
-    fn extract_module_items(&mut self, items: Vec<syn::Item>, is_root: bool) -> syn::Result<()> {
+    /// Extract all relevant items from a module at `depth` (see `from_module`)
+    /// The errors of invalid items are added to `diagnostics`, the items themselves are left out
+    fn extract_module_items(
+        &mut self,
+        items: Vec<syn::Item>,
+        depth: usize,
+        diagnostics: &mut Diagnostics
+    ) {
+        let is_root = depth == 0;
         let mut has_default_tusk = false;
+        let parameters_name = TusksParameters::find_name(&items);
         for item in items {
             match item {
                 syn::Item::Struct(item_struct) => {
-                    self.parse_struct(item_struct.clone())?;
+                    diagnostics.check(self.parse_struct(item_struct.clone(), depth));
                 }
 
                 syn::Item::Fn(item_fn) => {
-                    if let Some(tusk) = Tusk::from_fn(
+                    if !self.is_command_candidate(&item_fn, &item_fn.vis, &item_fn.sig.ident, "function") {
+                        continue;
+                    }
+
+                    // An invalid #[default] function still counts for the duplicate check
+                    let marks_default = item_fn.has_attr("default")
+                        && !item_fn.has_attr("skip")
+                        && is_eligible_visibility(&item_fn.vis);
+
+                    let tusk = Tusk::from_fn(
                         item_fn.clone(),
                         has_default_tusk,
-                        self.allow_external_subcommands
-                    )? {
-                        has_default_tusk = has_default_tusk || tusk.is_default;
+                        self.allow_external_subcommands,
+                        &parameters_name
+                    );
+                    if let Some(tusk) = diagnostics.check(tusk).flatten() {
+                        diagnostics.check(check_reachable_visibility(
+                            &tusk.func.vis,
+                            depth,
+                            &tusk.func.sig.ident,
+                            "function"
+                        ));
                         self.tusks.push(tusk);
                     }
+
+                    has_default_tusk = has_default_tusk || marks_default;
                 }
                 
                 syn::Item::Mod(item_mod) => {
-                    if let Some(module) = Self::from_module(item_mod.clone(), false, false)? {
+                    if !self.is_command_candidate(&item_mod, &item_mod.vis, &item_mod.ident, "submodule") {
+                        continue;
+                    }
+
+                    let module = Self::from_module(
+                        item_mod.clone(),
+                        false,
+                        depth + 1,
+                        self.explicit,
+                        self.rename_all
+                    );
+                    if let Some(module) = diagnostics.check(module).flatten() {
+                        diagnostics.check(
+                            check_reachable_visibility(&item_mod.vis, depth, &module.name, "submodule")
+                        );
                         self.submodules.push(module);
                     }
                 }
                 
                 syn::Item::Use(item_use) => {
-                    // Only consider pub use
-                    if matches!(item_use.vis, syn::Visibility::Public(_)) {
+                    // The parent_ module is declared via pub use as well, it needs no marker
+                    let is_command = self.is_command_candidate(
+                        &item_use,
+                        &item_use.vis,
+                        &use_tree_ident(&item_use.tree),
+                        "external module"
+                    );
+
+                    // Only consider pub use (or pub(crate) use, ...)
+                    if is_eligible_visibility(&item_use.vis) {
                         // Extract external modules
-                        self.extract_external_modules(&item_use.tree, &item_use, is_root);
+                        let known = self.external_modules.len();
+                        self.extract_external_modules(&item_use.tree, &item_use, is_root, is_command);
+                        for external_module in &self.external_modules[known..] {
+                            diagnostics.check(check_reachable_visibility(
+                                &item_use.vis,
+                                depth,
+                                &external_module.alias,
+                                "external module"
+                            ));
+                        }
                     }
                 }
                 
@@ -123,12 +294,56 @@ impl TusksModule {
                 }
             }
         }
-        Ok(())
     }
     
+    /// Checks if an item may become a command: in explicit mode only items marked with
+    /// `#[command]` or `#[tusk]` do. Marked items which are private are never commands,
+    /// a warning tells about it.
+    fn is_command_candidate(
+        &mut self,
+        item: &impl AttributeCheck,
+        vis: &syn::Visibility,
+        ident: &Ident,
+        kind: &str
+    ) -> bool {
+        let is_marked = item.has_command_marker();
+
+        if is_marked && !is_eligible_visibility(vis) && !item.has_attr("skip") {
+            self.warnings.push((ident.span(), format!(
+                "{} `{}` is marked as a command but is private, so it is not a command \
+                    (make it pub or pub(crate))",
+                kind,
+                ident
+            )));
+        }
+
+        is_marked || !self.explicit
+    }
+
     /// Parse a struct and check if it's a parameters struct
-    fn parse_struct(&mut self, item_struct: ItemStruct) -> syn::Result<()> {
+    fn parse_struct(&mut self, item_struct: ItemStruct, depth: usize) -> syn::Result<()> {
         if let Some(params) = TusksParameters::from_struct(item_struct)? {
+            check_reachable_visibility(
+                &params.pstruct.vis,
+                depth,
+                &params.pstruct.ident,
+                "parameters struct"
+            )?;
+            if let Some(ref existing) = self.parameters {
+                let mut error = syn::Error::new_spanned(
+                    &params.pstruct.ident,
+                    format!(
+                        "module `{}` has more than one parameters struct, \
+                            mark the other structs with #[skip]",
+                        self.name
+                    )
+                );
+                error.combine(syn::Error::new_spanned(
+                    &existing.pstruct.ident,
+                    "the first parameters struct is declared here"
+                ));
+                return Err(error);
+            }
             self.parameters = Some(params);
         }
         Ok(())
@@ -139,12 +354,13 @@ fn extract_external_modules(
     &mut self,
     tree: &syn::UseTree,
     item_use: &syn::ItemUse,
-    is_root: bool
+    is_root: bool,
+    is_command: bool
 ) {
     match tree {
         syn::UseTree::Path(use_path) => {
             // use foo::<rest>
-            self.extract_external_modules(&use_path.tree, item_use, is_root);
+            self.extract_external_modules(&use_path.tree, item_use, is_root, is_command);
         }
         syn::UseTree::Name(use_name) => {
             // Check if it's parent_
@@ -153,7 +369,7 @@ fn extract_external_modules(
                     alias: use_name.ident.clone(),
                     item_use: item_use.clone(),
                 });
-            } else {
+            } else if is_command {
                 self.external_modules.push(ExternalModule {
                     alias: use_name.ident.clone(),
                     item_use: item_use.clone(),
@@ -167,7 +383,7 @@ fn extract_external_modules(
                     alias: use_rename.rename.clone(),
                     item_use: item_use.clone(),
                 });
-            } else {
+            } else if is_command {
                 self.external_modules.push(ExternalModule {
                     alias: use_rename.rename.clone(),
                     item_use: item_use.clone(),
@@ -180,9 +396,180 @@ fn extract_external_modules(
         syn::UseTree::Group(use_group) => {
             // e.g. use foo::{bar, baz};
             for item in &use_group.items {
-                self.extract_external_modules(item, item_use, is_root);
+                self.extract_external_modules(item, item_use, is_root, is_command);
             }
         }
     }
 }
 }
+
+/// The name a use tree imports, e.g. `deploy` for `pub use crate::deploy;` or `ops` for
+/// `pub use crate::cloud::{self as ops};`
+fn use_tree_ident(tree: &syn::UseTree) -> Ident {
+    match tree {
+        syn::UseTree::Path(use_path) => use_tree_ident(&use_path.tree),
+        syn::UseTree::Name(use_name) => use_name.ident.clone(),
+        syn::UseTree::Rename(use_rename) => use_rename.rename.clone(),
+        syn::UseTree::Group(use_group) => use_group.items.first()
+            .map(use_tree_ident)
+            .unwrap_or_else(|| Ident::new("_", proc_macro2::Span::call_site())),
+        syn::UseTree::Glob(glob) => Ident::new("_", glob.star_token.span),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_explicit_mode_only_takes_marked_items() {
+        let module: ItemMod = parse_quote! {
+            pub mod cli {
+                #[command]
+                pub fn deploy() {}
+                pub fn helper() -> String { String::new() }
+                #[command]
+                fn private() {}
+                pub mod util { pub fn run() {} }
+            }
+        };
+
+        let tusks_module = TusksModule::from_module(module, true, 0, true, None).unwrap().unwrap();
+        let tusks: Vec<String> = tusks_module.tusks.iter()
+            .map(|tusk| tusk.func.sig.ident.to_string())
+            .collect();
+        assert_eq!(tusks, ["deploy"]);
+        assert!(tusks_module.submodules.is_empty());
+        assert_eq!(tusks_module.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_root_rename_rule_applies_while_parsing() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub fn foo_bar() {}
+                #[command(name = "foo-bar")]
+                pub fn other() {}
+                pub mod db { pub fn run_migrations() {} }
+            }
+        };
+
+        let tusks_module = TusksModule::from_module(
+            module,
+            true,
+            0,
+            false,
+            Some(RenameRule::SnakeCase)
+        ).unwrap().unwrap();
+        assert_eq!(tusks_module.rename_all, Some(RenameRule::SnakeCase));
+        assert_eq!(tusks_module.submodules[0].rename_all, Some(RenameRule::SnakeCase));
+    }
+
+    #[test]
+    fn test_names_collide_without_root_rename_rule() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub fn foo_bar() {}
+                #[command(name = "foo-bar")]
+                pub fn other() {}
+            }
+        };
+
+        let Err(error) = TusksModule::from_module(module, true, 0, false, None) else {
+            panic!("expected a name collision");
+        };
+        assert!(error.to_string().contains("both generate the command name `foo-bar`"));
+    }
+
+    #[test]
+    fn test_nested_restricted_visibilities() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub(super) fn status() {}
+                pub mod db {
+                    pub(super) fn migrate() {}
+                    pub(super) mod backup {
+                        pub(super) fn run() {}
+                        pub(crate) fn restore() {}
+                        pub(in self) fn helper() {}
+                    }
+                }
+            }
+        };
+
+        let Err(error) = TusksModule::from_module(module, true, 0, false, None) else {
+            panic!("expected a visibility error");
+        };
+        let messages: Vec<String> = error.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, [
+            "function `run` is `pub(super)`, which the generated code cannot access: \
+                use `pub` or `pub(crate)`",
+        ]);
+    }
+
+    #[test]
+    fn test_pub_in_self_is_private() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub fn status() {}
+                pub(in self) fn helper() {}
+                pub(in self) mod util { pub fn run() {} }
+            }
+        };
+
+        let tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        assert_eq!(tusks_module.tusks.len(), 1);
+        assert!(tusks_module.submodules.is_empty());
+    }
+
+    #[test]
+    fn test_more_than_one_parameters_struct() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub struct Parameters<'a> { pub verbose: &'a bool }
+                #[parameters]
+                pub struct CliOpts<'a> { pub quiet: &'a bool }
+                #[skip]
+                #[parameters]
+                pub struct Unused {}
+            }
+        };
+
+        let Err(error) = TusksModule::from_module(module, true, 0, false, None) else {
+            panic!("expected an error");
+        };
+        let messages: Vec<String> = error.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, [
+            "module `cli` has more than one parameters struct, mark the other structs with #[skip]",
+            "the first parameters struct is declared here",
+        ]);
+    }
+
+    #[test]
+    fn test_inherit_env_prefix() {
+        let module: ItemMod = syn::parse_quote! {
+            pub mod cli {
+                pub mod deploy {
+                    pub fn run() {}
+                    pub mod awsCloud { pub fn upload() {} }
+                }
+                pub mod db_tools { pub fn migrate() {} }
+            }
+        };
+
+        let mut tusks_module = TusksModule::from_module(module, true, 0, false, None)
+            .unwrap()
+            .unwrap();
+        tusks_module.inherit_env_prefix(Some("MYTOOL"));
+
+        let deploy = &tusks_module.submodules[0];
+        assert_eq!(tusks_module.env_prefix.as_deref(), Some("MYTOOL"));
+        assert_eq!(deploy.env_prefix.as_deref(), Some("MYTOOL_DEPLOY"));
+        assert_eq!(deploy.submodules[0].env_prefix.as_deref(), Some("MYTOOL_DEPLOY_AWS_CLOUD"));
+        assert_eq!(tusks_module.submodules[1].env_prefix.as_deref(), Some("MYTOOL_DB_TOOLS"));
+
+        tusks_module.inherit_env_prefix(None);
+        assert!(tusks_module.submodules[0].submodules[0].env_prefix.is_none());
+    }
+}
@@ -1,50 +1,140 @@
-use syn::ItemStruct;
+use proc_macro2::Span;
+use syn::{Field, Ident, Item, ItemStruct};
 use crate::parsing::util::attr::AttributeCheck;
 
-use crate::models::TusksParameters;
+use crate::models::{FieldConversion, TusksParameters};
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::parsing::util::visibility::is_eligible_visibility;
 
 impl TusksParameters {
     pub fn from_struct(item_struct: ItemStruct) -> syn::Result<Option<Self>> {
-        // Check if struct name is "Parameters"
-        if item_struct.ident != "Parameters" {
-            return Ok(None);
-        }
-        
-        if item_struct.has_attr("skip") {
+        if !Self::is_parameters_struct(&item_struct) {
             return Ok(None);
         }
 
         // Validate that the struct is public
-        if !matches!(item_struct.vis, syn::Visibility::Public(_)) {
+        if !is_eligible_visibility(&item_struct.vis) {
             return Err(syn::Error::new_spanned(
                 &item_struct.ident,
-                "Parameters struct must be public"
+                format!(
+                    "{} struct must be public (pub, pub(crate), pub(super), ...)",
+                    item_struct.ident
+                )
             ));
         }
 
-        // Validate that all fields are references
+        // Validate the fields and collect the conversions of owned fields
+        let mut diagnostics = Diagnostics::new();
+        let mut conversions = Vec::new();
         for field in &item_struct.fields {
             // Check for super_ field, which is not allowed
             if let Some(field_name) = &field.ident {
                 if field_name == "super_" {
-                    return Err(syn::Error::new_spanned(
+                    diagnostics.push(syn::Error::new_spanned(
                         field_name,
                         "super_ field is not allowed in Parameters struct. \
                             It will be added programmatically."
                     ));
                 }
             }
-            
-            if !Self::is_reference_type(&field.ty) {
-                return Err(syn::Error::new_spanned(
-                    &field.ty,
-                    "all fields in Parameters struct must be & references"
-                ));
+
+            if let Some(conversion) = diagnostics.check(Self::parse_conversion(field)).flatten() {
+                conversions.push(conversion);
             }
         }
 
-        Ok(Some(TusksParameters {
+        diagnostics.finish_with(Some(TusksParameters {
             pstruct: item_struct,
+            conversions,
+        }))
+    }
+
+    /// Checks if a struct holds the parameters of its module: the struct named `Parameters` or
+    /// the one marked with `#[parameters]`, unless it is marked with `#[skip]`
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[parameters]
+    /// pub struct DeployOpts<'a> {
+    ///     #[arg(long)]
+    ///     pub region: &'a String,
+    /// }
+    /// ```
+    pub fn is_parameters_struct(item_struct: &ItemStruct) -> bool {
+        (item_struct.ident == "Parameters" || item_struct.has_attr("parameters"))
+            && !item_struct.has_attr("skip")
+    }
+
+    /// The name of the parameters struct among the items of a module (see `is_parameters_struct`),
+    /// `Parameters` if there is none, as that is the name of the generated struct
+    pub fn find_name(items: &[Item]) -> Ident {
+        items.iter()
+            .find_map(|item| match item {
+                Item::Struct(item_struct) if Self::is_parameters_struct(item_struct) => {
+                    Some(item_struct.ident.clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| Ident::new("Parameters", Span::call_site()))
+    }
+
+    /// Parse `#[tusks(from = path::to::fn, arg_type = Type)]` on a field.
+    /// `arg_type` is optional and defaults to `String`.
+    ///
+    /// `from` takes infallible conversions, `fn(&Arg) -> T`. Conversions returning a
+    /// `Result<T, E>` with `E: Display` are declared with `try_from` instead; an error exits
+    /// like a clap error for an invalid value.
+    fn parse_conversion(field: &Field) -> syn::Result<Option<FieldConversion>> {
+        let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("tusks")) else {
+            return Ok(None);
+        };
+
+        let mut from: Option<(syn::Path, bool)> = None;
+        let mut arg_type: Option<syn::Type> = None;
+
+        attr.parse_nested_meta(|meta| {
+            let fallible = meta.path.is_ident("try_from");
+            if meta.path.is_ident("from") || fallible {
+                if from.is_some() {
+                    return Err(meta.error("only one of `from` and `try_from` can be set"));
+                }
+                from = Some((meta.value()?.parse()?, fallible));
+                Ok(())
+            } else if meta.path.is_ident("arg_type") {
+                arg_type = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unknown parameters field attribute, expected `from`, `try_from` or `arg_type`"
+                ))
+            }
+        })?;
+
+        let Some((from, fallible)) = from else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "missing conversion function: #[tusks(from = path::to::fn)] \
+                    or #[tusks(try_from = path::to::fn)]"
+            ));
+        };
+
+        // The converted value is owned by the Parameters struct, it cannot be borrowed
+        if Self::is_reference_type(&field.ty) {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "fields with a #[tusks(from = ...)] conversion must not be references"
+            ));
+        }
+
+        let Some(field_name) = &field.ident else {
+            return Err(syn::Error::new_spanned(field, "Parameters fields must be named"));
+        };
+
+        Ok(Some(FieldConversion {
+            field: field_name.clone(),
+            from,
+            fallible,
+            arg_type: arg_type.unwrap_or_else(|| syn::parse_quote! { String }),
         }))
     }
 
@@ -53,3 +143,89 @@ impl TusksParameters {
         matches!(ty, syn::Type::Reference(_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use syn::parse_quote;
+
+    fn field(item_struct: ItemStruct) -> Field {
+        item_struct.fields.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_parse_conversion() {
+        let plain = field(parse_quote! { pub struct Parameters { #[arg(long)] pub name: String } });
+        assert!(TusksParameters::parse_conversion(&plain).unwrap().is_none());
+
+        let from = field(parse_quote! {
+            pub struct Parameters { #[tusks(from = load_config)] pub config: Config }
+        });
+        let conversion = TusksParameters::parse_conversion(&from).unwrap().unwrap();
+        assert_eq!(conversion.field, "config");
+        assert!(conversion.from.is_ident("load_config"));
+        assert!(!conversion.fallible);
+        let arg_type = &conversion.arg_type;
+        assert_eq!(quote! { #arg_type }.to_string(), "String");
+
+        let try_from = field(parse_quote! {
+            pub struct Parameters {
+                #[tusks(try_from = self::load, arg_type = PathBuf)]
+                pub config: Config
+            }
+        });
+        let conversion = TusksParameters::parse_conversion(&try_from).unwrap().unwrap();
+        assert!(conversion.fallible);
+        let arg_type = &conversion.arg_type;
+        assert_eq!(quote! { #arg_type }.to_string(), "PathBuf");
+    }
+
+    #[test]
+    fn test_parse_invalid_conversion() {
+        let error = |item_struct: ItemStruct| {
+            TusksParameters::parse_conversion(&field(item_struct)).err().unwrap().to_string()
+        };
+
+        assert!(error(parse_quote! {
+            pub struct Parameters { #[tusks(arg_type = PathBuf)] pub config: Config }
+        }).starts_with("missing conversion function"));
+        assert!(error(parse_quote! {
+            pub struct Parameters { #[tusks(from = load, try_from = load)] pub config: Config }
+        }).starts_with("only one of `from` and `try_from`"));
+        assert!(error(parse_quote! {
+            pub struct Parameters { #[tusks(into = load)] pub config: Config }
+        }).starts_with("unknown parameters field attribute"));
+        assert!(error(parse_quote! {
+            pub struct Parameters<'a> { #[tusks(from = load)] pub config: &'a Config }
+        }).ends_with("must not be references"));
+    }
+
+    #[test]
+    fn test_is_parameters_struct() {
+        let is_parameters = |item_struct: ItemStruct| {
+            TusksParameters::is_parameters_struct(&item_struct)
+        };
+
+        assert!(is_parameters(parse_quote! { pub struct Parameters {} }));
+        assert!(is_parameters(parse_quote! { #[parameters] pub struct DeployOpts {} }));
+        assert!(!is_parameters(parse_quote! { pub struct DeployOpts {} }));
+        assert!(!is_parameters(parse_quote! { #[skip] pub struct Parameters {} }));
+        assert!(!is_parameters(parse_quote! { #[parameters] #[skip] pub struct DeployOpts {} }));
+    }
+
+    #[test]
+    fn test_find_name() {
+        let items: Vec<Item> = vec![
+            parse_quote! { pub struct Config {} },
+            parse_quote! { #[parameters] pub struct DeployOpts<'a> { pub region: &'a String } },
+        ];
+        assert_eq!(TusksParameters::find_name(&items), "DeployOpts");
+
+        let items: Vec<Item> = vec![
+            parse_quote! { #[skip] pub struct Parameters {} },
+            parse_quote! { pub fn run() {} },
+        ];
+        assert_eq!(TusksParameters::find_name(&items), "Parameters");
+    }
+}
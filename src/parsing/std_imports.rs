@@ -0,0 +1,78 @@
+use syn::{Item, UseTree};
+
+use crate::models::StdImports;
+
+impl StdImports {
+    /// The borrowed std types imported by the `use` items among `items`, also via a group
+    /// (`use std::{ffi::OsStr, path::Path};`) or a glob (`use std::path::*;`).
+    /// Imports under another name are not followed.
+    pub fn from_items(items: &[Item]) -> Self {
+        let mut imports = StdImports::default();
+        for item in items {
+            if let Item::Use(item_use) = item {
+                imports.add_use_tree(&item_use.tree, &mut Vec::new());
+            }
+        }
+        imports
+    }
+
+    /// Add the types imported by `tree`, which is below the path `prefix`
+    fn add_use_tree(&mut self, tree: &UseTree, prefix: &mut Vec<String>) {
+        match tree {
+            UseTree::Path(use_path) => {
+                prefix.push(use_path.ident.to_string());
+                self.add_use_tree(&use_path.tree, prefix);
+                prefix.pop();
+            }
+            UseTree::Name(use_name) => self.add(prefix, Some(&use_name.ident.to_string())),
+            UseTree::Rename(use_rename) if use_rename.ident == use_rename.rename => {
+                self.add(prefix, Some(&use_rename.ident.to_string()));
+            }
+            UseTree::Rename(_) => {}
+            UseTree::Glob(_) => self.add(prefix, None),
+            UseTree::Group(use_group) => {
+                for tree in &use_group.items {
+                    self.add_use_tree(tree, prefix);
+                }
+            }
+        }
+    }
+
+    /// Add the type `name` of the module `prefix`, or all of its types for a glob
+    fn add(&mut self, prefix: &[String], name: Option<&str>) {
+        let [krate, module] = prefix else {
+            return;
+        };
+        match (krate.as_str(), module.as_str(), name) {
+            ("std", "path", None | Some("Path")) => self.path = true,
+            ("std", "ffi", None | Some("OsStr")) => self.os_str = true,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn imports(file: syn::File) -> StdImports {
+        StdImports::from_items(&file.items)
+    }
+
+    #[test]
+    fn test_std_imports() {
+        assert_eq!(imports(parse_quote! { use std::path::Path; }), StdImports { path: true, os_str: false });
+        assert_eq!(
+            imports(parse_quote! { use ::std::{ffi::OsStr, path::{Path, PathBuf}}; }),
+            StdImports { path: true, os_str: true }
+        );
+        assert_eq!(imports(parse_quote! { use std::ffi::*; }), StdImports { path: false, os_str: true });
+        assert!(imports(parse_quote! { pub use std::path::Path as Path; }).path);
+
+        assert_eq!(imports(parse_quote! { use std::path::PathBuf; }), StdImports::default());
+        assert_eq!(imports(parse_quote! { use std::path::Path as StdPath; }), StdImports::default());
+        assert_eq!(imports(parse_quote! { use crate::routes::Path; }), StdImports::default());
+        assert_eq!(imports(parse_quote! { use std::*; }), StdImports::default());
+    }
+}
@@ -2,34 +2,47 @@ use syn::ItemFn;
 use crate::parsing::util::attr::AttributeCheck;
 
 use crate::models::Tusk;
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::parsing::util::visibility::is_eligible_visibility;
 
 impl Tusk {
     pub fn from_fn(
         item_fn: ItemFn,
         default_exists: bool,
-        allow_external_subcommands: bool
+        allow_external_subcommands: bool,
+        parameters_name: &syn::Ident
     ) -> syn::Result<Option<Self>> {
-        // Only consider pub functions
-        if !matches!(item_fn.vis, syn::Visibility::Public(_)) || item_fn.has_attr("skip") {
+        // Only consider pub (or pub(crate), ...) functions
+        if !is_eligible_visibility(&item_fn.vis) || item_fn.has_attr("skip") {
             return Ok(None);
         }
 
-        // Validate return type is either nothing, i32, or Option<i32>
-        Self::validate_return_type(&item_fn.sig.output)?;
+        let mut diagnostics = Diagnostics::new();
+
+        // Validate return type is either nothing, u8, Option<u8> or a Result of those
+        diagnostics.check(Self::validate_return_type(&item_fn.sig.output));
 
         let is_default = item_fn.has_attr("default");
 
         if is_default {
-            default_function::validate(&item_fn, default_exists, allow_external_subcommands)?;
+            diagnostics.extend(
+                default_function::validate(
+                    &item_fn,
+                    default_exists,
+                    allow_external_subcommands,
+                    parameters_name
+                )
+            );
         }
 
-        Ok(Some(Tusk {
+        diagnostics.finish_with(Some(Tusk {
             func: item_fn,
             is_default
         }))
     }
     
-    /// Validate that the return type is either nothing, u8, or Option<u8>
+    /// Validate that the return type is either nothing, u8, or Option<u8>,
+    /// or a Result with (), u8 or Option<u8> as its Ok type
     fn validate_return_type(output: &syn::ReturnType) -> syn::Result<()> {
         match output {
             syn::ReturnType::Default => {
@@ -39,17 +52,71 @@ impl Tusk {
             syn::ReturnType::Type(_, ty) => {
                 // Check if it's u8 or Option<u8>
                 if Self::is_u8_type(ty) || Self::is_option_u8_type(ty) {
-                    Ok(())
-                } else {
-                    Err(syn::Error::new_spanned(
-                        ty,
-                        "command function must return (), u8, or Option<u8>"
-                    ))
+                    return Ok(());
+                }
+
+                // Check if it's Result<(), E>, Result<u8, E> or Result<Option<u8>, E>
+                let is_valid_result = Self::result_ok_type(ty).is_some_and(|ok_ty| {
+                    Self::is_unit_type(ok_ty)
+                        || Self::is_u8_type(ok_ty)
+                        || Self::is_option_u8_type(ok_ty)
+                });
+                if is_valid_result {
+                    return Ok(());
                 }
+
+                Err(syn::Error::new_spanned(
+                    ty,
+                    "command function must return (), u8, Option<u8>, \
+                        or a Result<T, E> where T is (), u8 or Option<u8>"
+                ))
             }
         }
     }
 
+    /// Check if a type is ()
+    pub fn is_unit_type(ty: &syn::Type) -> bool {
+        matches!(ty, syn::Type::Tuple(tuple) if tuple.elems.is_empty())
+    }
+
+    /// Get the Ok type of a Result type, e.g. u8 for Result<u8, MyError>
+    ///
+    /// Aliases with a fixed error type like io::Result<u8> are recognized as well.
+    pub fn result_ok_type(ty: &syn::Type) -> Option<&syn::Type> {
+        let syn::Type::Path(type_path) = ty else {
+            return None;
+        };
+
+        let segment = type_path.path.segments.last()?;
+
+        if segment.ident != "Result" {
+            return None;
+        }
+
+        let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return None;
+        };
+
+        let syn::GenericArgument::Type(ok_ty) = args.args.first()? else {
+            return None;
+        };
+
+        Some(ok_ty)
+    }
+
+    /// Get the type of the function argument with the given name
+    pub fn argument_type(&self, name: &syn::Ident) -> Option<&syn::Type> {
+        self.func.sig.inputs.iter().find_map(|input| {
+            let syn::FnArg::Typed(pat_type) = input else {
+                return None;
+            };
+            match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) if pat_ident.ident == *name => Some(&*pat_type.ty),
+                _ => None,
+            }
+        })
+    }
+
     /// Check if a type is u8
     pub fn is_u8_type(ty: &syn::Type) -> bool {
         let syn::Type::Path(type_path) = ty else {
@@ -94,15 +161,20 @@ impl Tusk {
 }
 
 mod default_function {
-    use syn::ItemFn;
+    use syn::{Ident, ItemFn};
 
+    /// All problems of a #[default] function. `parameters_name` is the name of the parameters
+    /// struct of the module, `Parameters` unless another struct is marked with #[parameters].
     pub fn validate(
         item_fn: &ItemFn,
         default_exists: bool,
-        allow_external_subcommands: bool
-    ) -> syn::Result<()> {
-        check_duplicate_default(item_fn, default_exists)?;
-        validate_default_function_arguments(item_fn, allow_external_subcommands)
+        allow_external_subcommands: bool,
+        parameters_name: &Ident
+    ) -> Vec<syn::Error> {
+        [
+            check_duplicate_default(item_fn, default_exists),
+            validate_default_function_arguments(item_fn, allow_external_subcommands, parameters_name),
+        ].into_iter().filter_map(Result::err).collect()
     }
 
     fn check_duplicate_default(item_fn: &ItemFn, default_exists: bool) -> syn::Result<()> {
@@ -119,33 +191,40 @@ mod default_function {
 
     fn validate_default_function_arguments(
         item_fn: &ItemFn,
-        allow_external_subcommands: bool
+        allow_external_subcommands: bool,
+        parameters_name: &Ident
     ) -> syn::Result<()> {
         match item_fn.sig.inputs.len() {
             0 => Ok(()),
-            1 => validate_single_argument(&item_fn.sig.inputs[0], allow_external_subcommands),
+            1 => validate_single_argument(
+                &item_fn.sig.inputs[0],
+                allow_external_subcommands,
+                parameters_name
+            ),
             2 => validate_two_arguments(
                 &item_fn.sig.inputs[0],
                 &item_fn.sig.inputs[1],
-                allow_external_subcommands
+                allow_external_subcommands,
+                parameters_name
             ),
             _ => Err(syn::Error::new_spanned(
                 &item_fn.sig.inputs,
-                error_message_too_many_args(allow_external_subcommands)
+                error_message_too_many_args(allow_external_subcommands, parameters_name)
             ))
         }
     }
 
     fn validate_single_argument(
         arg: &syn::FnArg,
-        allow_external_subcommands: bool
+        allow_external_subcommands: bool,
+        parameters_name: &Ident
     ) -> syn::Result<()> {
         let syn::FnArg::Typed(pat_type) = arg else {
-            return Err(error_single_argument(arg, allow_external_subcommands));
+            return Err(error_single_argument(arg, allow_external_subcommands, parameters_name));
         };
 
         // Check if it's &Parameters (reference without path)
-        if is_parameters_reference(&pat_type.ty) {
+        if is_parameters_reference(&pat_type.ty, parameters_name) {
             return Ok(());
         }
 
@@ -158,44 +237,48 @@ mod default_function {
             }
         }
 
-        Err(error_single_argument(arg, allow_external_subcommands))
+        Err(error_single_argument(arg, allow_external_subcommands, parameters_name))
     }
 
     fn validate_two_arguments(
         arg1: &syn::FnArg,
         arg2: &syn::FnArg,
-        allow_external_subcommands: bool
+        allow_external_subcommands: bool,
+        parameters_name: &Ident
     ) -> syn::Result<()> {
         if !allow_external_subcommands {
             return Err(syn::Error::new_spanned(
                 quote::quote! { #arg1, #arg2 },
-                "default function must have either no arguments \
-                    or exactly one argument of type &Parameters"
+                format!(
+                    "default function must have either no arguments \
+                        or exactly one argument of type &{}",
+                    parameters_name
+                )
             ));
         }
 
         let (syn::FnArg::Typed(pat_type1), syn::FnArg::Typed(pat_type2)) = (arg1, arg2) else {
-            return Err(error_two_arguments_signature(arg1, arg2));
+            return Err(error_two_arguments_signature(arg1, arg2, parameters_name));
         };
 
         // First must be &Parameters, second must be Vec<String>
-        if !is_parameters_reference(&pat_type1.ty) {
-            return Err(error_two_arguments_signature(arg1, arg2));
+        if !is_parameters_reference(&pat_type1.ty, parameters_name) {
+            return Err(error_two_arguments_signature(arg1, arg2, parameters_name));
         }
 
         let syn::Type::Path(type_path2) = &*pat_type2.ty else {
-            return Err(error_two_arguments_signature(arg1, arg2));
+            return Err(error_two_arguments_signature(arg1, arg2, parameters_name));
         };
 
         if !is_vec_string(type_path2) {
-            return Err(error_two_arguments_signature(arg1, arg2))
+            return Err(error_two_arguments_signature(arg1, arg2, parameters_name))
         }
 
         Ok(())
     }
 
-    /// Check if type is &Parameters (reference to Parameters without path)
-    fn is_parameters_reference(ty: &syn::Type) -> bool {
+    /// Check if type is &Parameters (reference to the parameters struct without path)
+    fn is_parameters_reference(ty: &syn::Type, parameters_name: &Ident) -> bool {
         let syn::Type::Reference(type_ref) = ty else {
             return false;
         };
@@ -204,43 +287,63 @@ mod default_function {
             return false;
         };
 
-        // Check that it's just "Parameters" without any path segments
+        // Check that it's just the struct name without any path segments
         type_path.path.segments.len() == 1 
-        && type_path.path.segments[0].ident == "Parameters"
+        && type_path.path.segments[0].ident == *parameters_name
         && type_path.qself.is_none()
     }
 
     fn error_single_argument(
         arg: &syn::FnArg,
-        allow_external_subcommands: bool
+        allow_external_subcommands: bool,
+        parameters_name: &Ident
     ) -> syn::Error {
         let message = if allow_external_subcommands {
-            "default function must have either no arguments, \
-                a &Parameters argument, \
-                a Vec<String> argument, \
-                or both (&Parameters, Vec<String>)"
+            format!(
+                "default function must have either no arguments, \
+                    a &{0} argument, \
+                    a Vec<String> argument, \
+                    or both (&{0}, Vec<String>)",
+                parameters_name
+            )
         } else {
-            "default function must have either no arguments \
-                or exactly one argument of type &Parameters"
+            format!(
+                "default function must have either no arguments \
+                    or exactly one argument of type &{}",
+                parameters_name
+            )
         };
         syn::Error::new_spanned(arg, message)
     }
 
-    fn error_two_arguments_signature(arg1: &syn::FnArg, arg2: &syn::FnArg) -> syn::Error {
+    fn error_two_arguments_signature(
+        arg1: &syn::FnArg,
+        arg2: &syn::FnArg,
+        parameters_name: &Ident
+    ) -> syn::Error {
         syn::Error::new_spanned(
             quote::quote! { #arg1, #arg2 },
-            "default function with two arguments must have signature: \
-                (&Parameters, Vec<String>)"
+            format!(
+                "default function with two arguments must have signature: \
+                    (&{}, Vec<String>)",
+                parameters_name
+            )
         )
     }
 
-    fn error_message_too_many_args(allow_external_subcommands: bool) -> &'static str {
+    fn error_message_too_many_args(allow_external_subcommands: bool, parameters_name: &Ident) -> String {
         if allow_external_subcommands {
-            "default function must have at most two arguments: \
-                &Parameters and Vec<String>"
+            format!(
+                "default function must have at most two arguments: \
+                    &{} and Vec<String>",
+                parameters_name
+            )
         } else {
-            "default function must have either no arguments \
-                or exactly one argument of type &Parameters"
+            format!(
+                "default function must have either no arguments \
+                    or exactly one argument of type &{}",
+                parameters_name
+            )
         }
     }
 
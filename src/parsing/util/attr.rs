@@ -8,6 +8,12 @@ pub trait HasAttributes {
 /// Public trait for attribute checking
 pub trait AttributeCheck {
     fn has_attr(&self, name: &str) -> bool;
+
+    /// Checks for one of the attributes marking an item as a command in explicit mode:
+    /// `#[command]`, `#[tusk]` or (for functions) `#[default]`
+    fn has_command_marker(&self) -> bool {
+        self.has_attr("command") || self.has_attr("tusk") || self.has_attr("default")
+    }
 }
 
 /* -------------------------------------------------------
@@ -0,0 +1,81 @@
+/// Collects the errors of all parsing and codegen steps, so that a single expansion reports
+/// every problem instead of only the first one.
+///
+/// # Example
+/// ```ignore
+/// let mut diagnostics = Diagnostics::new();
+/// for item_fn in functions {
+///     if let Some(tusk) = diagnostics.check(Tusk::from_fn(item_fn, false, false, &parameters_name)) {
+///         tusks.extend(tusk);
+///     }
+/// }
+/// diagnostics.finish()?;
+/// ```
+#[derive(Default)]
+pub struct Diagnostics {
+    error: Option<syn::Error>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an error (which may already hold several combined errors)
+    pub fn push(&mut self, error: syn::Error) {
+        match self.error {
+            Some(ref mut existing) => existing.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+
+    /// Record the error of `result`, if any, and return its value otherwise
+    pub fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    /// All recorded errors combined into one
+    pub fn finish(self) -> syn::Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// `value` if no error was recorded, all recorded errors combined otherwise
+    pub fn finish_with<T>(self, value: T) -> syn::Result<T> {
+        self.finish().map(|()| value)
+    }
+}
+
+impl Extend<syn::Error> for Diagnostics {
+    fn extend<I: IntoIterator<Item = syn::Error>>(&mut self, errors: I) {
+        errors.into_iter().for_each(|error| self.push(error));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    #[test]
+    fn test_combines_all_errors() {
+        let mut diagnostics = Diagnostics::new();
+        assert_eq!(diagnostics.check(Ok::<_, syn::Error>(1)), Some(1));
+        diagnostics.push(syn::Error::new(Span::call_site(), "first"));
+        assert_eq!(diagnostics.check(Err::<u8, _>(syn::Error::new(Span::call_site(), "second"))), None);
+
+        let messages: Vec<String> = diagnostics.finish().unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(messages, ["first", "second"]);
+    }
+}
@@ -0,0 +1,172 @@
+use proc_macro2::Span;
+use syn::{Attribute, Expr, Lit, Meta};
+
+/// The documentation of a single function argument from an `# Arguments` doc section
+pub struct ArgumentDoc {
+    /// Name of the documented argument
+    pub name: String,
+
+    /// The description, used as help text of the argument
+    pub help: String,
+
+    /// Span of the doc line naming the argument
+    pub span: Span,
+}
+
+/// A single line of a doc comment
+pub struct DocLine {
+    pub text: String,
+    pub span: Span,
+}
+
+/// Split all `#[doc = "..."]` attributes into lines.
+/// Docs which are no string literals (e.g. `#[doc = include_str!(...)]`) are skipped.
+pub fn doc_lines(attrs: &[Attribute]) -> Vec<DocLine> {
+    let mut lines = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        let Meta::NameValue(name_value) = &attr.meta else {
+            continue;
+        };
+        let Expr::Lit(expr_lit) = &name_value.value else {
+            continue;
+        };
+        let Lit::Str(lit_str) = &expr_lit.lit else {
+            continue;
+        };
+
+        // Not `lines()`, which drops empty `///` lines separating paragraphs
+        for text in lit_str.value().split('\n') {
+            lines.push(DocLine {
+                text: text.trim_end_matches('\r').to_string(),
+                span: lit_str.span()
+            });
+        }
+    }
+
+    lines
+}
+
+/// Check whether a doc line is the heading of the `# Arguments` section
+fn is_arguments_heading(line: &str) -> bool {
+    line.trim() == "# Arguments"
+}
+
+/// Check whether a doc line is a heading ending the `# Arguments` section
+fn is_heading(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+/// Parse the rustdoc-style `# Arguments` section of a doc comment.
+///
+/// # Example
+/// ```ignore
+/// /// Deploy the application
+/// ///
+/// /// # Arguments
+/// /// * `region` - The region to deploy to
+/// /// * `dry_run` - Only print what would be done,
+/// ///   without changing anything
+/// ```
+/// yields `region` → "The region to deploy to" and
+/// `dry_run` → "Only print what would be done, without changing anything"
+pub fn parse_arguments_section(attrs: &[Attribute]) -> Vec<ArgumentDoc> {
+    let mut arguments: Vec<ArgumentDoc> = Vec::new();
+    let mut in_section = false;
+
+    for line in doc_lines(attrs) {
+        if is_arguments_heading(&line.text) {
+            in_section = true;
+            continue;
+        }
+
+        if !in_section {
+            continue;
+        }
+
+        if is_heading(&line.text) {
+            break;
+        }
+
+        let trimmed = line.text.trim();
+        if let Some(entry) = trimmed.strip_prefix("* ").or_else(|| trimmed.strip_prefix("- ")) {
+            if let Some((name, help)) = parse_argument_entry(entry) {
+                arguments.push(ArgumentDoc { name, help, span: line.span });
+            }
+        } else if let Some(last) = arguments.last_mut() {
+            // Continuation line of the previous entry
+            if !trimmed.is_empty() {
+                last.help.push(' ');
+                last.help.push_str(trimmed);
+            }
+        }
+    }
+
+    arguments
+}
+
+/// Parse an entry like `` `name` - description `` or `name: description`
+fn parse_argument_entry(entry: &str) -> Option<(String, String)> {
+    let (name, help) = entry.split_once(" - ").or_else(|| entry.split_once(": "))?;
+    let name = name.trim().trim_matches('`').to_string();
+
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some((name, help.trim().to_string()))
+}
+
+/// All doc lines except the `# Arguments` section, which is shown per argument instead
+pub fn doc_lines_without_arguments_section(attrs: &[Attribute]) -> Vec<DocLine> {
+    let mut in_section = false;
+
+    doc_lines(attrs).into_iter().filter(|line| {
+        if is_arguments_heading(&line.text) {
+            in_section = true;
+        } else if in_section && is_heading(&line.text) {
+            in_section = false;
+        }
+        !in_section
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{ItemFn, parse_quote};
+
+    #[test]
+    fn test_parse_arguments_section() {
+        let item: ItemFn = parse_quote! {
+            /// Deploy the application
+            ///
+            /// # Arguments
+            /// * `region` - The region to deploy to
+            /// * `dry_run` - Only print what would be done,
+            ///   without changing anything
+            ///
+            /// # Examples
+            /// * `ignored` - not an argument
+            fn deploy(region: String, dry_run: bool) {}
+        };
+
+        let arguments = parse_arguments_section(&item.attrs);
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].name, "region");
+        assert_eq!(arguments[0].help, "The region to deploy to");
+        assert_eq!(arguments[1].name, "dry_run");
+        assert_eq!(arguments[1].help, "Only print what would be done, without changing anything");
+
+        let remaining: Vec<_> = doc_lines_without_arguments_section(&item.attrs)
+            .into_iter()
+            .map(|line| line.text)
+            .collect();
+        assert_eq!(remaining, [
+            " Deploy the application",
+            "",
+            " # Examples",
+            " * `ignored` - not an argument"
+        ]);
+    }
+}
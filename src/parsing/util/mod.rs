@@ -1,2 +1,5 @@
 pub mod attr;
 pub mod get_attribute_value;
+pub mod doc_comment;
+pub mod diagnostics;
+pub mod visibility;
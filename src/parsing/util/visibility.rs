@@ -0,0 +1,109 @@
+use quote::quote;
+use syn::{Ident, Visibility};
+
+/// Checks if an item is eligible as a command, submodule, external module or Parameters struct.
+///
+/// Besides `pub` this holds for restricted visibilities like `pub(crate)` or `pub(super)`, so
+/// commands do not have to be part of the public API of the crate. Whether the generated code
+/// can access a restricted item depends on where it is declared, which
+/// `check_reachable_visibility` checks. Private items, `pub(self)` and `pub(in self)` (which are
+/// the same as private) are not eligible.
+///
+/// # Example
+/// ```ignore
+/// pub fn deploy() {}             // command
+/// pub(crate) fn migrate() {}     // command
+/// pub(self) fn helper() {}       // no command
+/// fn other_helper() {}           // no command
+/// ```
+pub fn is_eligible_visibility(vis: &Visibility) -> bool {
+    match vis {
+        Visibility::Public(_) => true,
+        Visibility::Restricted(restricted) => !restricted.path.is_ident("self"),
+        Visibility::Inherited => false,
+    }
+}
+
+/// Checks that the generated code, which lives in a child module of the tusks module, can access
+/// an eligible item declared `depth` modules below the tusks module (0 for its own items).
+///
+/// `pub` and `pub(crate)` always work. `pub(super)` only reaches the generated code for the
+/// items of the tusks module and of its direct submodules; deeper down it is visible in the
+/// parent module only. `pub(in path)` is rejected as well, use `pub(crate)` instead.
+///
+/// # Errors
+/// Returns an error pointing to the visibility of the item `ident` of the given `kind`
+pub fn check_reachable_visibility(
+    vis: &Visibility,
+    depth: usize,
+    ident: &Ident,
+    kind: &str
+) -> syn::Result<()> {
+    let Visibility::Restricted(restricted) = vis else {
+        return Ok(());
+    };
+
+    let path = &restricted.path;
+    if path.is_ident("crate") || (path.is_ident("super") && depth <= 1) {
+        return Ok(());
+    }
+
+    let path = quote! { #path }.to_string().replace(' ', "");
+    let vis = match restricted.in_token {
+        Some(_) => format!("pub(in {})", path),
+        None => format!("pub({})", path),
+    };
+    Err(syn::Error::new_spanned(
+        restricted,
+        format!(
+            "{} `{}` is `{}`, which the generated code cannot access: use `pub` or `pub(crate)`",
+            kind,
+            ident,
+            vis
+        )
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_restricted_visibilities_are_eligible() {
+        let eligible: Vec<bool> = [
+            parse_quote!(pub),
+            parse_quote!(pub(crate)),
+            parse_quote!(pub(super)),
+            parse_quote!(pub(in crate::cli)),
+            parse_quote!(pub(self)),
+            parse_quote!(pub(in self)),
+            Visibility::Inherited,
+        ].iter().map(is_eligible_visibility).collect();
+
+        assert_eq!(eligible, [true, true, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn test_reachable_visibilities() {
+        let ident: Ident = parse_quote!(deploy);
+        let reachable = |vis: Visibility, depth| {
+            check_reachable_visibility(&vis, depth, &ident, "function").is_ok()
+        };
+
+        assert!(reachable(parse_quote!(pub), 3));
+        assert!(reachable(parse_quote!(pub(crate)), 3));
+        assert!(reachable(parse_quote!(pub(super)), 0));
+        assert!(reachable(parse_quote!(pub(super)), 1));
+        assert!(!reachable(parse_quote!(pub(super)), 2));
+        assert!(!reachable(parse_quote!(pub(in crate::cli)), 0));
+
+        let vis: Visibility = parse_quote!(pub(in super::super));
+        let error = check_reachable_visibility(&vis, 0, &ident, "function").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "function `deploy` is `pub(in super::super)`, which the generated code cannot access: \
+                use `pub` or `pub(crate)`"
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use syn::{Attribute, Ident, Item, ItemMod};
+
+use crate::TusksModule;
+use crate::parsing::util::diagnostics::Diagnostics;
+use crate::codegen::util::arg_attribute::long_name;
+use crate::codegen::util::command_attribute::command_name;
+use crate::codegen::util::enum_util::{
+    convert_external_module_to_enum_variant,
+    convert_function_to_enum_variant,
+    convert_submodule_to_enum_variant,
+    ident_name
+};
+
+/// Names used by the generated code, which must not be used for submodules, external modules
+/// or Parameters fields. Command functions are only called by their path, so they may use them.
+pub const RESERVED_NAMES: &[&str] = &[
+    "sub",
+    "super_",
+    "_phantom_lifetime_marker",
+    "parameters",
+    "cli",
+    "__internal_tusks_module",
+    "tusks_config_file",
+];
+
+/// Functions generated into the root module: `exec_cli`, and `write_manpages` with
+/// `#[tusks(manpages)]`
+pub const ROOT_FUNCTIONS: &[&str] = &["exec_cli", "write_manpages"];
+
+/// Variants of the generated `Commands` enums
+pub const RESERVED_VARIANTS: &[&str] = &["TuskExternalCommands", "ClapExternalSubcommand"];
+
+/// A command of a module as it appears in the generated code
+struct CommandEntry<'a> {
+    ident: &'a Ident,
+    kind: &'static str,
+    variant: Ident,
+    name: String,
+    /// External modules live in their own `ExternalCommands` enum
+    is_external: bool,
+    /// Submodules and external modules, whose names are placed next to the generated modules
+    is_module: bool,
+}
+
+impl TusksModule {
+    /// Checks the module tree for names that clash in the generated code:
+    /// - two commands with the same enum variant (e.g. `fn deploy` and `mod deploy`)
+    /// - two commands with the same command line name (e.g. two `#[command(name = "x")]`)
+    /// - submodules, external modules or Parameters fields using a reserved name
+    ///
+    /// # Errors
+    /// Returns all conflicts combined. Each one points to both offending items.
+    pub fn validate_names(&self) -> syn::Result<()> {
+        let mut diagnostics = Diagnostics::new();
+        self.collect_name_errors(&mut diagnostics);
+        diagnostics.finish()
+    }
+
+    /// Checks that no Parameters field takes the long option `--config`, which a root module
+    /// with `config_file` adds to all commands
+    ///
+    /// # Errors
+    /// Returns one error per clashing field.
+    pub fn validate_config_option(&self) -> syn::Result<()> {
+        let mut diagnostics = Diagnostics::new();
+        if self.config_file.is_some() {
+            self.collect_config_option_errors(&mut diagnostics);
+        }
+        diagnostics.finish()
+    }
+
+    fn collect_config_option_errors(&self, diagnostics: &mut Diagnostics) {
+        if let Some(ref params) = self.parameters {
+            for field in &params.pstruct.fields {
+                if let (Some(ident), Some("config")) = (&field.ident, long_name(field).as_deref()) {
+                    diagnostics.push(syn::Error::new_spanned(
+                        ident,
+                        format!(
+                            "Parameters field `{}` takes the option `--config`, which `config_file` \
+                                adds for the path of the config file",
+                            ident_name(ident)
+                        )
+                    ));
+                }
+            }
+        }
+
+        for submodule in &self.submodules {
+            submodule.collect_config_option_errors(diagnostics);
+        }
+    }
+
+    fn collect_name_errors(&self, diagnostics: &mut Diagnostics) {
+        let entries = self.command_entries();
+
+        for (index, entry) in entries.iter().enumerate() {
+            if entry.is_module {
+                diagnostics.extend(reserved_name_error(entry.ident, entry.kind));
+            }
+
+            if !entry.is_external && RESERVED_VARIANTS.iter().any(|name| entry.variant == name) {
+                diagnostics.push(syn::Error::new_spanned(
+                    entry.ident,
+                    format!(
+                        "{} `{}` becomes the variant `{}`, which is reserved for the generated \
+                            code (reserved variants: {})",
+                        entry.kind,
+                        ident_name(entry.ident),
+                        entry.variant,
+                        RESERVED_VARIANTS.join(", ")
+                    )
+                ));
+            }
+
+            diagnostics.extend(collision_error(&entries[..index], entry));
+        }
+
+        if let Some(ref params) = self.parameters {
+            for field in &params.pstruct.fields {
+                // super_ is rejected with a more specific message when parsing the struct
+                if let Some(field_name) = field.ident.as_ref().filter(|name| *name != "super_") {
+                    diagnostics.extend(reserved_name_error(field_name, "Parameters field"));
+                }
+            }
+        }
+
+        for submodule in &self.submodules {
+            submodule.collect_name_errors(diagnostics);
+        }
+    }
+
+    /// All commands of this module with their variant and command line name
+    fn command_entries(&self) -> Vec<CommandEntry<'_>> {
+        let entry = |ident, kind, variant, attrs: Vec<&Attribute>, is_external| CommandEntry {
+            ident,
+            kind,
+            variant,
+            name: command_name(ident, self.rename_all, &attrs),
+            is_external,
+            is_module: kind != "function",
+        };
+
+        let tusks = self.tusks.iter().map(|tusk| entry(
+            &tusk.func.sig.ident,
+            "function",
+            convert_function_to_enum_variant(&tusk.func.sig.ident),
+            tusk.extract_attributes(&["command"]),
+            false
+        ));
+
+        let submodules = self.submodules.iter().map(|submodule| entry(
+            &submodule.name,
+            "submodule",
+            convert_submodule_to_enum_variant(&submodule.name),
+            submodule.extract_attributes(&["command"]),
+            false
+        ));
+
+        let external_modules = self.external_modules.iter().map(|ext_mod| entry(
+            &ext_mod.alias,
+            "external module",
+            convert_external_module_to_enum_variant(&ext_mod.alias),
+            ext_mod.extract_attributes(&["command"]),
+            true
+        ));
+
+        tusks.chain(submodules).chain(external_modules).collect()
+    }
+}
+
+/// Checks that no function of the root module, command or not, uses the name of a function
+/// generated next to it (see `ROOT_FUNCTIONS`). `write_manpages` is only generated with
+/// `manpages`.
+///
+/// # Errors
+/// Returns one error per clashing function.
+pub fn validate_root_functions(module: &ItemMod, manpages: bool) -> syn::Result<()> {
+    let mut diagnostics = Diagnostics::new();
+    let Some((_, ref items)) = module.content else {
+        return Ok(());
+    };
+
+    for item in items {
+        let Item::Fn(item_fn) = item else {
+            continue;
+        };
+        let name = ident_name(&item_fn.sig.ident);
+        if name == "exec_cli" || (manpages && name == "write_manpages") {
+            diagnostics.push(syn::Error::new_spanned(
+                &item_fn.sig.ident,
+                format!(
+                    "function `{}` clashes with the function tusks generates in the root module \
+                        (reserved functions: {})",
+                    name,
+                    ROOT_FUNCTIONS.join(", ")
+                )
+            ));
+        }
+    }
+    diagnostics.finish()
+}
+
+/// Error for an item using one of the `RESERVED_NAMES`
+fn reserved_name_error(ident: &Ident, kind: &str) -> Option<syn::Error> {
+    let name = ident_name(ident);
+    if !RESERVED_NAMES.contains(&name.as_str()) {
+        return None;
+    }
+
+    Some(syn::Error::new_spanned(
+        ident,
+        format!(
+            "{} `{}` uses a name reserved for the generated code (reserved names: {})",
+            kind,
+            name,
+            RESERVED_NAMES.join(", ")
+        )
+    ))
+}
+
+/// Error for a command clashing with one of the commands declared before it
+fn collision_error(previous: &[CommandEntry], entry: &CommandEntry) -> Option<syn::Error> {
+    let (other, reason) = previous.iter().find_map(|other| {
+        if other.is_external == entry.is_external && other.variant == entry.variant {
+            Some((other, format!("the enum variant `{}`", entry.variant)))
+        } else if other.name == entry.name {
+            Some((other, format!("the command name `{}`", entry.name)))
+        } else {
+            None
+        }
+    })?;
+
+    let mut error = syn::Error::new_spanned(
+        entry.ident,
+        format!(
+            "{} `{}` collides with {} `{}`: both generate {}",
+            entry.kind,
+            ident_name(entry.ident),
+            other.kind,
+            ident_name(other.ident),
+            reason
+        )
+    );
+    error.combine(syn::Error::new_spanned(
+        other.ident,
+        format!("{} `{}` is declared here", other.kind, ident_name(other.ident))
+    ));
+    Some(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_collisions_point_to_both_items() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod tasks {
+                pub fn deploy() {}
+                pub mod deploy { pub fn run() {} }
+            }
+        };
+
+        let Err(error) = TusksModule::from_module(module, true, 0, false, None) else {
+            panic!("expected a collision error");
+        };
+        let messages: Vec<String> = error.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages, [
+            "submodule `deploy` collides with function `deploy`: both generate the enum variant `Deploy`",
+            "function `deploy` is declared here",
+        ]);
+    }
+
+    fn messages(module: syn::ItemMod) -> Vec<String> {
+        match TusksModule::from_module(module, true, 0, false, None) {
+            Ok(_) => Vec::new(),
+            Err(error) => error.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_reserved_names_accepted_for_functions() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod tasks {
+                pub fn cli() {}
+                pub fn parameters() {}
+                pub mod db { pub fn sub() {} }
+            }
+        };
+
+        assert!(messages(module).is_empty());
+    }
+
+    #[test]
+    fn test_reserved_names_rejected_for_modules_and_fields() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod tasks {
+                pub struct Parameters<'a> {
+                    pub cli: &'a bool,
+                }
+                pub mod parameters { pub fn run() {} }
+                pub use crate::other::cli;
+            }
+        };
+
+        let messages = messages(module);
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].starts_with("submodule `parameters` uses a name reserved"));
+        assert!(messages[1].starts_with("external module `cli` uses a name reserved"));
+        assert!(messages[2].starts_with("Parameters field `cli` uses a name reserved"));
+    }
+
+    #[test]
+    fn test_config_option_clashes() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod tasks {
+                pub struct Parameters<'a> {
+                    #[arg(long)]
+                    pub config: &'a String,
+                    #[arg(short)]
+                    pub settings: &'a String,
+                }
+                pub mod deploy {
+                    pub struct Parameters<'a> {
+                        #[arg(long = "config")]
+                        pub target: &'a String,
+                    }
+                    pub fn run() {}
+                }
+            }
+        };
+
+        let mut tusks_module = TusksModule::from_module(module, true, 0, false, None).unwrap().unwrap();
+        assert!(tusks_module.validate_config_option().is_ok());
+
+        tusks_module.config_file = Some("tool.toml".to_string());
+        let messages: Vec<String> = tusks_module.validate_config_option().unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("Parameters field `config` takes the option `--config`"));
+        assert!(messages[1].starts_with("Parameters field `target` takes the option `--config`"));
+    }
+
+    #[test]
+    fn test_root_functions() {
+        let module: syn::ItemMod = parse_quote! {
+            pub mod tasks {
+                pub fn exec_cli() {}
+                #[skip]
+                pub fn write_manpages() {}
+                pub mod db { pub fn exec_cli() {} }
+            }
+        };
+
+        let messages = |manpages| -> Vec<String> {
+            match validate_root_functions(&module, manpages) {
+                Ok(()) => Vec::new(),
+                Err(error) => error.into_iter().map(|e| e.to_string()).collect(),
+            }
+        };
+        assert_eq!(messages(false).len(), 1);
+        assert!(messages(false)[0].starts_with("function `exec_cli` clashes with the function tusks generates"));
+        assert_eq!(messages(true).len(), 2);
+        assert!(messages(true)[1].starts_with("function `write_manpages` clashes"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Runtime support for the completion of task names in tasks mode.
+//!
+//! The generated `__complete-tasks` command uses this module (via `::tusks::task_names`) to
+//! flatten the command tree into the task names the shell offers, e.g. `db.migrate`.
+
+/// A command of the tree, as far as the task names are concerned
+pub struct TaskCommand {
+    pub name: String,
+    pub hidden: bool,
+    pub subcommands: Vec<TaskCommand>,
+}
+
+/// The task names of the subcommands of the root, joined by `separator`. Commands nested
+/// deeper than `max_depth` are not listed themselves, their ancestor at `max_depth` is.
+/// Hidden commands and clap's `help` are left out.
+///
+/// # Example
+/// With `separator = "."` and `max_depth = 2`, `db migrate up` is listed as `db.migrate`.
+pub fn task_names(root: &TaskCommand, separator: &str, max_depth: usize) -> Vec<String> {
+    let mut names = Vec::new();
+    collect(root, "", 1, separator, max_depth, &mut names);
+    names
+}
+
+fn collect(
+    command: &TaskCommand,
+    path: &str,
+    depth: usize,
+    separator: &str,
+    max_depth: usize,
+    names: &mut Vec<String>
+) {
+    for subcommand in &command.subcommands {
+        if subcommand.hidden || subcommand.name == "help" {
+            continue;
+        }
+
+        let name = if path.is_empty() {
+            subcommand.name.clone()
+        } else {
+            format!("{}{}{}", path, separator, subcommand.name)
+        };
+
+        if !subcommand.subcommands.is_empty() && depth < max_depth {
+            collect(subcommand, &name, depth + 1, separator, max_depth, names);
+        } else {
+            names.push(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str, subcommands: Vec<TaskCommand>) -> TaskCommand {
+        TaskCommand { name: name.to_string(), hidden: false, subcommands }
+    }
+
+    fn tree() -> TaskCommand {
+        command("tool", vec![
+            command("build", vec![]),
+            command("db", vec![
+                command("migrate", vec![command("up", vec![]), command("down", vec![])]),
+                command("help", vec![]),
+            ]),
+            TaskCommand { hidden: true, ..command("__complete-tasks", vec![]) },
+            command("help", vec![]),
+        ])
+    }
+
+    #[test]
+    fn test_task_names() {
+        assert_eq!(task_names(&tree(), ".", 5), ["build", "db.migrate.up", "db.migrate.down"]);
+        assert_eq!(task_names(&tree(), ":", 3), ["build", "db:migrate:up", "db:migrate:down"]);
+    }
+
+    #[test]
+    fn test_task_names_stop_at_max_depth() {
+        assert_eq!(task_names(&tree(), ".", 2), ["build", "db.migrate"]);
+        assert_eq!(task_names(&tree(), ".", 1), ["build", "db"]);
+    }
+}